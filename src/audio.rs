@@ -1,29 +1,166 @@
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
-use cpal::{Device, Stream, StreamConfig};
+use cpal::{Device, SampleFormat, SizedSample, Stream, StreamConfig, SupportedStreamConfigRange};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use crate::resampler::Resampler;
+use crate::ring_buffer::{ring_buffer, Consumer};
+use crate::sample_convert::{to_mono, ToF32};
 
 const WHISPER_SAMPLE_RATE: u32 = 16000;
 const SLIDING_WINDOW_DURATION_MS: u64 = 5000; // Keep 5 seconds of context
 
+/// How long to keep looking for a replacement microphone after the current one fails
+const DEVICE_RECOVERY_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Delay between attempts to attach a replacement microphone
+const DEVICE_RETRY_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Audio the ring between the callback and the event loop can hold before dropping samples
+const RING_BUFFER_MS: u64 = 5000;
+
 pub struct AudioCapture {
     device: Device,
     config: StreamConfig,
-    buffer: Arc<Mutex<Vec<f32>>>,
+    sample_format: SampleFormat,
+    /// Configured device name (or substring); None = system default
+    preferred_device: Option<String>,
+    /// Receives mono samples from the audio callback (one ring per stream)
+    input: Mutex<Option<InputRing>>,
+    /// Samples collected from the ring, at the device sample rate
+    buffer: Mutex<Vec<f32>>,
+    /// Samples lost this recording because the ring was full
+    dropped_samples: AtomicU64,
     sliding_window: Arc<Mutex<Vec<f32>>>, // Last 5 seconds for context
     stream: Option<Stream>,
+    /// True from start to stop, even while the stream is being replaced
+    recording: bool,
     last_chunk_time: Arc<Mutex<std::time::Instant>>,
+    resampler: Mutex<Resampler>, // Keeps filter state across chunks
+    /// Audio from a previous device, already resampled, not yet returned
+    carried_over: Mutex<Vec<f32>>,
+    /// Set by the stream error callback (e.g. the device was unplugged)
+    stream_failed: Arc<AtomicBool>,
+    /// When the current stream failed, while looking for a replacement device
+    failed_since: Option<Instant>,
+    last_recovery_attempt: Option<Instant>,
+    /// Always-on capture: the stream stays open between recordings, keeping this much audio
+    pre_roll_ms: Option<u64>,
+    /// Audio from just before the current recording started, resampled, not yet taken
+    pre_roll: Mutex<Vec<f32>>,
+}
+
+struct InputRing {
+    consumer: Consumer,
+    /// Drop count already reported
+    dropped_seen: u64,
+}
+
+/// Names of all available input devices, and the name of the default one
+pub fn list_input_devices() -> Result<(Vec<String>, Option<String>)> {
+    let host = cpal::default_host();
+    let names = host
+        .input_devices()
+        .context("Failed to enumerate input devices")?
+        .filter_map(|device| device.name().ok())
+        .collect();
+    let default = host.default_input_device().and_then(|device| device.name().ok());
+    Ok((names, default))
+}
+
+/// Find the device matching `requested`: an exact (case-insensitive) name match wins,
+/// otherwise the first device whose name contains it
+pub fn match_device_name(names: &[String], requested: &str) -> Option<usize> {
+    let requested = requested.trim().to_lowercase();
+    if requested.is_empty() {
+        return None;
+    }
+
+    names
+        .iter()
+        .position(|name| name.to_lowercase() == requested)
+        .or_else(|| names.iter().position(|name| name.to_lowercase().contains(&requested)))
+}
+
+/// Whether the stream callback can convert this sample format
+fn is_supported_format(format: SampleFormat) -> bool {
+    matches!(
+        format,
+        SampleFormat::I8
+            | SampleFormat::I16
+            | SampleFormat::I32
+            | SampleFormat::I64
+            | SampleFormat::U8
+            | SampleFormat::U16
+            | SampleFormat::U32
+            | SampleFormat::U64
+            | SampleFormat::F32
+            | SampleFormat::F64
+    )
+}
+
+/// The configured input device if it is connected, otherwise the system default
+fn find_input_device(preferred: Option<&str>) -> Result<Device> {
+    let host = cpal::default_host();
+
+    if let Some(requested) = preferred {
+        let mut devices: Vec<Device> = host
+            .input_devices()
+            .context("Failed to enumerate input devices")?
+            .collect();
+        let names: Vec<String> = devices
+            .iter()
+            .map(|device| device.name().unwrap_or_default())
+            .collect();
+
+        if let Some(index) = match_device_name(&names, requested) {
+            return Ok(devices.swap_remove(index));
+        }
+        println!("⚠️  Audio device \"{}\" not found, using the default input device", requested);
+        println!("   Run `live-transcribe list-devices` to see available devices");
+    }
+
+    host.default_input_device()
+        .context("No input device available")
 }
 
 impl AudioCapture {
     pub fn new() -> Result<Self> {
-        let host = cpal::default_host();
+        Self::with_device(None)
+    }
 
-        // Get default input device
-        let device = host
-            .default_input_device()
-            .context("No input device available")?;
+    /// Capture from the input device whose name matches `preferred` (exact or substring),
+    /// falling back to the system default if it isn't connected
+    pub fn with_device(preferred: Option<&str>) -> Result<Self> {
+        let device = find_input_device(preferred)?;
+        let (config, sample_format) = Self::stream_config(&device)?;
+        let resampler = Resampler::new(config.sample_rate.0, WHISPER_SAMPLE_RATE);
+
+        Ok(AudioCapture {
+            device,
+            config,
+            sample_format,
+            preferred_device: preferred.map(|name| name.to_string()),
+            input: Mutex::new(None),
+            buffer: Mutex::new(Vec::new()),
+            dropped_samples: AtomicU64::new(0),
+            sliding_window: Arc::new(Mutex::new(Vec::new())),
+            stream: None,
+            recording: false,
+            last_chunk_time: Arc::new(Mutex::new(std::time::Instant::now())),
+            resampler: Mutex::new(resampler),
+            carried_over: Mutex::new(Vec::new()),
+            stream_failed: Arc::new(AtomicBool::new(false)),
+            failed_since: None,
+            last_recovery_attempt: None,
+            pre_roll_ms: None,
+            pre_roll: Mutex::new(Vec::new()),
+        })
+    }
 
+    /// Choose the stream configuration and sample format for a device, preferring 16kHz
+    fn stream_config(device: &Device) -> Result<(StreamConfig, SampleFormat)> {
         println!("Using audio input device: {}", device.name()?);
 
         // Get the default input config
@@ -34,49 +171,153 @@ impl AudioCapture {
         println!("Default config: {:?}", default_config);
 
         // Try to find a supported config close to what we want
-        let supported_configs = device
+        let supported_configs: Vec<SupportedStreamConfigRange> = device
             .supported_input_configs()
-            .context("Failed to query supported input configs")?;
+            .context("Failed to query supported input configs")?
+            .collect();
 
         println!("Supported configs:");
-        for (i, config) in supported_configs.enumerate() {
+        for (i, config) in supported_configs.iter().enumerate() {
             println!("  {}: {:?}", i, config);
         }
 
         // Use the default config but with our desired sample rate if supported
         let mut config: StreamConfig = default_config.clone().into();
-
-        // Check if 16kHz is supported
-        let supported_configs = device.supported_input_configs()?;
-        let mut found_16k = false;
-
-        for supported_config in supported_configs {
-            if supported_config.min_sample_rate().0 <= WHISPER_SAMPLE_RATE
-                && supported_config.max_sample_rate().0 >= WHISPER_SAMPLE_RATE {
-                found_16k = true;
-                config.sample_rate = cpal::SampleRate(WHISPER_SAMPLE_RATE);
-                break;
-            }
-        }
-
-        if !found_16k {
+        let mut sample_format = default_config.sample_format();
+
+        // Check if 16kHz is supported, preferring the default format and channel count
+        let best_16k = supported_configs
+            .iter()
+            .filter(|range| {
+                range.min_sample_rate().0 <= WHISPER_SAMPLE_RATE
+                    && range.max_sample_rate().0 >= WHISPER_SAMPLE_RATE
+                    && is_supported_format(range.sample_format())
+            })
+            .min_by_key(|range| {
+                (range.sample_format() != sample_format, range.channels() != config.channels)
+            });
+
+        if let Some(range) = best_16k {
+            config.sample_rate = cpal::SampleRate(WHISPER_SAMPLE_RATE);
+            config.channels = range.channels();
+            sample_format = range.sample_format();
+        } else {
             println!("Warning: 16kHz not supported, using default sample rate: {}", config.sample_rate.0);
             println!("Audio will be resampled during transcription");
         }
 
+        if !is_supported_format(sample_format) {
+            bail!("Unsupported input sample format: {:?}", sample_format);
+        }
+
         println!(
             "Final audio config: {} channels, {} Hz, {:?}",
-            config.channels, config.sample_rate.0, default_config.sample_format()
+            config.channels, config.sample_rate.0, sample_format
         );
 
-        Ok(AudioCapture {
-            device,
-            config,
-            buffer: Arc::new(Mutex::new(Vec::new())),
-            sliding_window: Arc::new(Mutex::new(Vec::new())),
-            stream: None,
-            last_chunk_time: Arc::new(Mutex::new(std::time::Instant::now())),
-        })
+        Ok((config, sample_format))
+    }
+
+    /// Switch to another device (only while not streaming from the current one)
+    fn attach(&mut self, device: Device) -> Result<()> {
+        let (config, sample_format) = Self::stream_config(&device)?;
+
+        // Audio captured from the old device still needs the old device's resampler
+        self.collect_input();
+        let pending = std::mem::take(&mut *self.buffer.lock().unwrap());
+        {
+            let mut resampler = self.resampler.lock().unwrap();
+            let mut carried_over = self.carried_over.lock().unwrap();
+            carried_over.extend(resampler.process(&pending));
+            carried_over.extend(resampler.flush());
+            *resampler = Resampler::new(config.sample_rate.0, WHISPER_SAMPLE_RATE);
+        }
+
+        self.device = device;
+        self.config = config;
+        self.sample_format = sample_format;
+        Ok(())
+    }
+
+    /// Reattach to a working microphone if the current one failed during recording
+    ///
+    /// Call this regularly while recording. The recording continues on the configured
+    /// device (if connected) or the system default. Returns an error once no device
+    /// could be attached for `DEVICE_RECOVERY_TIMEOUT`; the caller should then stop.
+    pub fn recover_if_disconnected(&mut self) -> Result<()> {
+        if !self.recording || !self.stream_failed.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        let failed_since = *self.failed_since.get_or_insert_with(|| {
+            eprintln!("🔌 Audio input device failed, looking for another one...");
+            Instant::now()
+        });
+
+        if let Some(last_attempt) = self.last_recovery_attempt {
+            if last_attempt.elapsed() < DEVICE_RETRY_INTERVAL {
+                return Ok(());
+            }
+        }
+        self.last_recovery_attempt = Some(Instant::now());
+
+        match self.reattach() {
+            Ok(()) => {
+                self.failed_since = None;
+                self.last_recovery_attempt = None;
+                println!("✓ Recording continues on {}", self.device.name().unwrap_or_default());
+                Ok(())
+            }
+            Err(e) if failed_since.elapsed() < DEVICE_RECOVERY_TIMEOUT => {
+                eprintln!("   Still waiting for an audio input device: {:#}", e);
+                Ok(())
+            }
+            Err(e) => Err(e.context("Lost the audio input device")),
+        }
+    }
+
+    fn reattach(&mut self) -> Result<()> {
+        // Release the dead stream before opening a new one
+        self.stream = None;
+
+        let device = find_input_device(self.preferred_device.as_deref())?;
+        self.attach(device)?;
+        self.stream_failed.store(false, Ordering::SeqCst);
+
+        let stream = self.build_stream()?;
+        stream.play().context("Failed to start audio stream")?;
+        self.stream = Some(stream);
+        Ok(())
+    }
+
+    /// Move captured audio out of the ring buffer
+    ///
+    /// Call this regularly while recording (at least every few seconds), otherwise the
+    /// ring fills up and samples are dropped. Drops are reported and counted.
+    pub fn collect_input(&self) {
+        let mut input = self.input.lock().unwrap();
+        let Some(input) = input.as_mut() else {
+            return;
+        };
+
+        input.consumer.pop_into(&mut self.buffer.lock().unwrap());
+
+        let dropped = input.consumer.dropped();
+        if dropped > input.dropped_seen {
+            let new_drops = dropped - input.dropped_seen;
+            input.dropped_seen = dropped;
+            self.dropped_samples.fetch_add(new_drops, Ordering::Relaxed);
+            eprintln!(
+                "⚠️  Audio input overflow: dropped {} samples ({:.0}ms)",
+                new_drops,
+                new_drops as f32 * 1000.0 / self.config.sample_rate.0 as f32
+            );
+        }
+    }
+
+    /// Samples lost in the current (or last) recording because the event loop fell behind
+    pub fn dropped_samples(&self) -> u64 {
+        self.dropped_samples.load(Ordering::Relaxed)
     }
 
     pub fn get_chunk_if_ready(&self, chunk_duration_ms: u64) -> Option<(Vec<f32>, usize)> {
@@ -86,13 +327,12 @@ impl AudioCapture {
         if now.duration_since(*last_time).as_millis() >= chunk_duration_ms as u128 {
             *last_time = now;
 
+            self.collect_input();
+
             // Minimize time holding the buffer lock - swap out the data instead of cloning
             let new_chunk = {
                 let mut buffer = self.buffer.lock().unwrap();
-                let buffer_len = buffer.len();
-                eprintln!("📦 get_chunk_if_ready: buffer has {} samples", buffer_len);
                 if buffer.is_empty() {
-                    eprintln!("⚠️  Buffer is empty, returning None");
                     return None;
                 }
                 // Use mem::take to swap out the buffer without cloning
@@ -101,12 +341,8 @@ impl AudioCapture {
             }; // Lock released here
 
             // Resample AFTER releasing the lock to avoid blocking audio thread
-            let actual_sample_rate = self.config.sample_rate.0;
-            let resampled_new = if actual_sample_rate != WHISPER_SAMPLE_RATE {
-                Self::resample(&new_chunk, actual_sample_rate, WHISPER_SAMPLE_RATE)
-            } else {
-                new_chunk
-            };
+            let mut resampled_new = std::mem::take(&mut *self.carried_over.lock().unwrap());
+            resampled_new.extend(self.resampler.lock().unwrap().process(&new_chunk));
 
             let new_samples_count = resampled_new.len();
 
@@ -156,78 +392,178 @@ impl AudioCapture {
         }
     }
 
+    /// Keep the input stream open between recordings (always-on capture)
+    ///
+    /// The last `pre_roll_ms` of audio before each recording is kept, so a word spoken as
+    /// the hotkey is pressed isn't clipped while the stream starts. Call `trim_pre_roll`
+    /// regularly between recordings.
+    pub fn start_monitoring(&mut self, pre_roll_ms: u64) -> Result<()> {
+        self.pre_roll_ms = Some(pre_roll_ms);
+        if self.stream.is_none() {
+            self.stream_failed.store(false, Ordering::SeqCst);
+            let stream = self.build_stream()?;
+            stream.play().context("Failed to start audio stream")?;
+            self.stream = Some(stream);
+        }
+        println!("🎙️  Always-on capture: keeping {}ms of audio before each recording", pre_roll_ms);
+        Ok(())
+    }
+
+    /// Discard idle audio older than the pre-roll (always-on capture, between recordings)
+    pub fn trim_pre_roll(&mut self) {
+        let Some(pre_roll_ms) = self.pre_roll_ms else {
+            return;
+        };
+        if self.recording || self.stream.is_none() {
+            return;
+        }
+
+        // A failed stream is reopened when the next recording starts
+        if self.stream_failed.load(Ordering::SeqCst) {
+            eprintln!("🔌 Audio input device failed while idle, pre-roll unavailable until the next recording");
+            self.stream = None;
+            *self.input.lock().unwrap() = None;
+            self.buffer.lock().unwrap().clear();
+            return;
+        }
+
+        self.collect_input();
+        let keep = (self.config.sample_rate.0 as u64 * pre_roll_ms / 1000) as usize;
+        let mut buffer = self.buffer.lock().unwrap();
+        if buffer.len() > keep {
+            let excess = buffer.len() - keep;
+            buffer.drain(0..excess);
+        }
+    }
+
+    /// Audio from just before the current recording started, at 16kHz (empty without
+    /// always-on capture)
+    pub fn take_pre_roll(&self) -> Vec<f32> {
+        std::mem::take(&mut *self.pre_roll.lock().unwrap())
+    }
+
     pub fn start_recording(&mut self) -> Result<()> {
-        if self.stream.is_some() {
+        if self.recording {
             return Ok(()); // Already recording
         }
 
+        // Pick up docking changes between recordings
+        let device = find_input_device(self.preferred_device.as_deref())?;
+        if device.name().ok() != self.device.name().ok() {
+            self.stream = None;
+            self.attach(device)?;
+        }
+
+        // An always-on stream keeps running; what it captured last becomes the pre-roll
+        let pre_roll = if self.stream.is_some() {
+            self.trim_pre_roll();
+            std::mem::take(&mut *self.buffer.lock().unwrap())
+        } else {
+            *self.input.lock().unwrap() = None;
+            Vec::new()
+        };
+
         // Clear the buffers
         self.buffer.lock().unwrap().clear();
+        self.dropped_samples.store(0, Ordering::Relaxed);
         self.sliding_window.lock().unwrap().clear();
+        self.resampler.lock().unwrap().reset();
+        self.carried_over.lock().unwrap().clear();
+        self.failed_since = None;
+        self.last_recovery_attempt = None;
+
+        // The recording continues where the pre-roll ends, so they share the resampler
+        *self.pre_roll.lock().unwrap() = self.resampler.lock().unwrap().process(&pre_roll);
+
+        if self.stream.is_none() {
+            self.stream_failed.store(false, Ordering::SeqCst);
+            let stream = self.build_stream()?;
+            stream.play().context("Failed to start audio stream")?;
+            self.stream = Some(stream);
+        }
 
-        let buffer = Arc::clone(&self.buffer);
-        let channels = self.config.channels as usize;
+        self.recording = true;
+        println!("Recording started");
+
+        Ok(())
+    }
+
+    fn build_stream(&self) -> Result<Stream> {
+        match self.sample_format {
+            SampleFormat::I8 => self.build_typed_stream::<i8>(),
+            SampleFormat::I16 => self.build_typed_stream::<i16>(),
+            SampleFormat::I32 => self.build_typed_stream::<i32>(),
+            SampleFormat::I64 => self.build_typed_stream::<i64>(),
+            SampleFormat::U8 => self.build_typed_stream::<u8>(),
+            SampleFormat::U16 => self.build_typed_stream::<u16>(),
+            SampleFormat::U32 => self.build_typed_stream::<u32>(),
+            SampleFormat::U64 => self.build_typed_stream::<u64>(),
+            SampleFormat::F32 => self.build_typed_stream::<f32>(),
+            SampleFormat::F64 => self.build_typed_stream::<f64>(),
+            other => bail!("Unsupported input sample format: {:?}", other),
+        }
+    }
 
-        // Track if we're receiving audio
-        let sample_counter = Arc::new(Mutex::new(0usize));
-        let counter_clone = Arc::clone(&sample_counter);
+    /// Build the input stream for sample type `T`, converting to f32 mono in the callback
+    ///
+    /// Each stream gets a new ring buffer; anything left in the previous one is collected first.
+    fn build_typed_stream<T: SizedSample + ToF32>(&self) -> Result<Stream> {
+        let channels = self.config.channels as usize;
+        let capacity = (self.config.sample_rate.0 as u64 * RING_BUFFER_MS / 1000) as usize;
+        let (mut producer, consumer) = ring_buffer(capacity);
 
-        let err_fn = |err| eprintln!("🔴 Audio stream error: {}", err);
+        // Errors are handled on the event loop thread by `recover_if_disconnected`
+        let stream_failed = Arc::clone(&self.stream_failed);
+        let err_fn = move |err| {
+            eprintln!("🔴 Audio stream error: {}", err);
+            stream_failed.store(true, Ordering::SeqCst);
+        };
 
         // Build the input stream
         let stream = self
             .device
             .build_input_stream(
                 &self.config,
-                move |data: &[f32], _: &cpal::InputCallbackInfo| {
-                    // Debug: log every second of audio received
-                    if let Ok(mut counter) = counter_clone.lock() {
-                        *counter += data.len();
-                        if *counter >= 48000 { // ~1 second at 48kHz
-                            eprintln!("🎤 Audio callback: received {}+ samples", *counter);
-                            *counter = 0;
-                        }
-                    }
-
-                    // Handle poisoned mutex gracefully in audio callback
-                    let Ok(mut buf) = buffer.lock() else {
-                        eprintln!("⚠️  Audio buffer mutex poisoned, dropping audio data");
-                        return;
-                    };
-
-                    // Convert to mono if needed and store samples
-                    if channels == 1 {
-                        buf.extend_from_slice(data);
-                    } else {
-                        // Average channels to get mono
-                        for chunk in data.chunks(channels) {
-                            let mono_sample: f32 = chunk.iter().sum::<f32>() / channels as f32;
-                            buf.push(mono_sample);
-                        }
-                    }
+                move |data: &[T], _: &cpal::InputCallbackInfo| {
+                    // Convert to f32 mono and hand off without blocking
+                    producer.push(to_mono(data, channels));
                 },
                 err_fn,
                 None,
             )
             .context("Failed to build input stream.\n\nThis is likely a microphone permissions issue.\nPlease grant microphone access:\n  1. Open System Settings → Privacy & Security → Microphone\n  2. Enable access for Terminal (or your terminal app)\n  3. Restart the app")?;
 
-        stream.play().context("Failed to start audio stream")?;
-
-        self.stream = Some(stream);
-        println!("Recording started");
+        self.collect_input();
+        *self.input.lock().unwrap() = Some(InputRing {
+            consumer,
+            dropped_seen: 0,
+        });
 
-        Ok(())
+        Ok(stream)
     }
 
     pub fn stop_recording(&mut self) -> Result<Vec<f32>> {
-        if let Some(stream) = self.stream.take() {
-            drop(stream);
-            println!("Recording stopped");
+        // With always-on capture the stream keeps running for the next pre-roll
+        if self.pre_roll_ms.is_none() {
+            if let Some(stream) = self.stream.take() {
+                drop(stream);
+                println!("Recording stopped");
+            }
+        }
+        self.recording = false;
+
+        self.collect_input();
+        if self.stream.is_none() {
+            *self.input.lock().unwrap() = None;
         }
 
-        let buffer = self.buffer.lock().unwrap();
-        let audio_data = buffer.clone();
-        drop(buffer); // Release lock before processing
+        // Take the audio out, so it isn't mistaken for pre-roll
+        let audio_data = std::mem::take(&mut *self.buffer.lock().unwrap());
+
+        let dropped = self.dropped_samples();
+        if dropped > 0 {
+            eprintln!("⚠️  {} samples were dropped during this recording", dropped);
+        }
 
         let actual_sample_rate = self.config.sample_rate.0;
 
@@ -237,61 +573,34 @@ impl AudioCapture {
             actual_sample_rate
         );
 
-        // Resample if needed
+        // Audio from a device used earlier in this recording
+        let mut output = std::mem::take(&mut *self.carried_over.lock().unwrap());
+
+        // Resample if needed, including the samples the filter was still holding back
         if actual_sample_rate != WHISPER_SAMPLE_RATE {
             println!("Resampling from {}Hz to {}Hz...", actual_sample_rate, WHISPER_SAMPLE_RATE);
-            let resampled = Self::resample(&audio_data, actual_sample_rate, WHISPER_SAMPLE_RATE);
+            let mut resampler = self.resampler.lock().unwrap();
+            let resampled = resampler.process(&audio_data);
             println!("Resampled to {} samples ({:.2}s)",
                 resampled.len(),
                 resampled.len() as f32 / WHISPER_SAMPLE_RATE as f32
             );
-            Ok(resampled)
+            output.extend(resampled);
+            output.extend(resampler.flush());
         } else {
-            Ok(audio_data)
-        }
-    }
-
-    // Simple linear interpolation resampling
-    fn resample(input: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
-        if from_rate == to_rate {
-            return input.to_vec();
-        }
-
-        let ratio = from_rate as f64 / to_rate as f64;
-        let output_len = (input.len() as f64 / ratio) as usize;
-        let mut output = Vec::with_capacity(output_len);
-
-        for i in 0..output_len {
-            let src_idx = i as f64 * ratio;
-            let src_idx_floor = src_idx.floor() as usize;
-            let src_idx_ceil = (src_idx_floor + 1).min(input.len() - 1);
-            let frac = src_idx - src_idx_floor as f64;
-
-            // Linear interpolation
-            let sample = input[src_idx_floor] * (1.0 - frac) as f32
-                + input[src_idx_ceil] * frac as f32;
-
-            output.push(sample);
+            output.extend(audio_data);
         }
 
-        output
+        Ok(output)
     }
 
     pub fn is_recording(&self) -> bool {
-        self.stream.is_some()
+        self.recording
     }
 
     // Simple energy-based silence detection
     pub fn is_silence(audio: &[f32], threshold: f32) -> bool {
-        if audio.is_empty() {
-            return true;
-        }
-
-        // Calculate RMS (Root Mean Square) energy
-        let sum_squares: f32 = audio.iter().map(|&x| x * x).sum();
-        let rms = (sum_squares / audio.len() as f32).sqrt();
-
-        rms < threshold
+        audio.is_empty() || crate::vad::rms(audio) < threshold
     }
 }
 
@@ -300,3 +609,23 @@ impl Drop for AudioCapture {
         let _ = self.stop_recording();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn test_match_device_name() {
+        let devices = names(&["MacBook Pro Microphone", "USB Audio Device", "USB Audio Device 2"]);
+
+        assert_eq!(match_device_name(&devices, "usb audio device 2"), Some(2));
+        assert_eq!(match_device_name(&devices, "USB Audio Device"), Some(1));
+        assert_eq!(match_device_name(&devices, "macbook"), Some(0));
+        assert_eq!(match_device_name(&devices, "Yeti"), None);
+        assert_eq!(match_device_name(&devices, "  "), None);
+    }
+}
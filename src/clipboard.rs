@@ -0,0 +1,78 @@
+//! System clipboard access
+//!
+//! Uses the platform's clipboard command-line tools: `pbcopy` on macOS, and `wl-copy`
+//! (Wayland) or `xclip`/`xsel` (X11) on Linux.
+
+use anyhow::{bail, Context, Result};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Copy text to the system clipboard
+pub fn copy_text(text: &str) -> Result<()> {
+    let commands = clipboard_commands();
+    if commands.is_empty() {
+        if cfg!(target_os = "linux") {
+            bail!(
+                "No Wayland or X11 display found (WAYLAND_DISPLAY and DISPLAY are unset), \
+                so wl-copy, xclip and xsel can't be used"
+            );
+        }
+        bail!("No clipboard available on this platform");
+    }
+
+    let mut last_error = None;
+    for (program, args) in commands {
+        match pipe_to_command(program, args, text) {
+            Ok(()) => {
+                println!("📋 Copied {} chars to clipboard", text.chars().count());
+                return Ok(());
+            }
+            Err(e) => last_error = Some(e),
+        }
+    }
+
+    Err(last_error.unwrap().context("Failed to copy to clipboard"))
+}
+
+/// Candidate clipboard commands for this platform, in order of preference
+fn clipboard_commands() -> Vec<(&'static str, &'static [&'static str])> {
+    let mut commands: Vec<(&'static str, &'static [&'static str])> = Vec::new();
+
+    if cfg!(target_os = "macos") {
+        commands.push(("pbcopy", &[]));
+    } else if cfg!(target_os = "linux") {
+        if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+            commands.push(("wl-copy", &[]));
+        }
+        if std::env::var_os("DISPLAY").is_some() {
+            commands.push(("xclip", &["-selection", "clipboard"]));
+            commands.push(("xsel", &["--clipboard", "--input"]));
+        }
+    }
+
+    commands
+}
+
+fn pipe_to_command(program: &str, args: &[&str], text: &str) -> Result<()> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .spawn()
+        .with_context(|| format!("Failed to execute {}", program))?;
+
+    // Close stdin after writing so the tool sees EOF
+    child
+        .stdin
+        .take()
+        .context("Failed to open clipboard tool stdin")?
+        .write_all(text.as_bytes())
+        .with_context(|| format!("Failed to write to {}", program))?;
+
+    let status = child.wait().with_context(|| format!("Failed to wait for {}", program))?;
+    if !status.success() {
+        bail!("{} exited with {}", program, status);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,186 @@
+//! Commit policies: when live transcription text becomes final
+//!
+//! With the `silence` policy every live preview is typed and then corrected by the VAD
+//! transcription once the speaker pauses. With `local_agreement` only the words that `n`
+//! consecutive live hypotheses agree on are typed (LocalAgreement-n), so typed text is
+//! never revised and run-on speech still produces stable text while it is spoken.
+
+use std::collections::VecDeque;
+use serde::{Deserialize, Serialize};
+
+/// When live transcription text is committed
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CommitPolicy {
+    /// Commit the VAD transcription after a pause; live previews are provisional
+    Silence,
+    /// Commit words as soon as consecutive live hypotheses agree on them
+    LocalAgreement,
+}
+
+/// LocalAgreement-n over the live hypotheses of one utterance
+pub struct LocalAgreement {
+    /// Number of consecutive hypotheses that must agree
+    n: usize,
+    /// The last `n` hypotheses, split into words
+    history: VecDeque<Vec<String>>,
+    /// Words confirmed so far; only ever grows until `take_confirmed` or `reset`
+    confirmed: Vec<String>,
+}
+
+impl LocalAgreement {
+    pub fn new(n: usize) -> Self {
+        Self {
+            n: n.max(1),
+            history: VecDeque::new(),
+            confirmed: Vec::new(),
+        }
+    }
+
+    /// Add the latest hypothesis for the utterance, returning how many words it confirmed
+    ///
+    /// The longest common word prefix of the last `n` hypotheses is agreed. Words beyond
+    /// the ones already confirmed are confirmed by position: if a hypothesis rewrites an
+    /// earlier confirmed word, the confirmed text is kept as it was.
+    pub fn insert(&mut self, hypothesis: &str) -> usize {
+        self.history.push_back(hypothesis.split_whitespace().map(str::to_string).collect());
+        if self.history.len() > self.n {
+            self.history.pop_front();
+        }
+        if self.history.len() < self.n {
+            return 0;
+        }
+
+        let latest = self.history.back().unwrap();
+        let agreed = latest
+            .iter()
+            .enumerate()
+            .take_while(|(i, word)| self.history.iter().all(|h| h.get(*i) == Some(*word)))
+            .count();
+        if agreed <= self.confirmed.len() {
+            return 0;
+        }
+
+        let newly_confirmed = agreed - self.confirmed.len();
+        self.confirmed.extend_from_slice(&latest[self.confirmed.len()..agreed]);
+        newly_confirmed
+    }
+
+    pub fn confirmed_words(&self) -> &[String] {
+        &self.confirmed
+    }
+
+    /// Confirmed words joined with spaces
+    pub fn confirmed_text(&self) -> String {
+        self.confirmed.join(" ")
+    }
+
+    /// Hand over the confirmed words of a committed utterance and start the next one
+    pub fn take_confirmed(&mut self) -> Vec<String> {
+        self.history.clear();
+        std::mem::take(&mut self.confirmed)
+    }
+
+    pub fn reset(&mut self) {
+        self.history.clear();
+        self.confirmed.clear();
+    }
+}
+
+/// Combine words already confirmed for an utterance with its final transcription
+///
+/// Confirmed words have been typed, so they are kept; the final transcription supplies
+/// the words after them. The last confirmed word may be extended (usually punctuation,
+/// like "test" becoming "test."), since that only appends to the typed text.
+pub fn reconcile(confirmed: &[String], final_text: &str) -> String {
+    let final_words: Vec<&str> = final_text.split_whitespace().collect();
+    let mut words = confirmed.to_vec();
+
+    if let Some(last) = words.last_mut() {
+        if let Some(word) = final_words.get(confirmed.len() - 1) {
+            if word.starts_with(last.as_str()) {
+                *last = word.to_string();
+            }
+        }
+    }
+
+    words.extend(final_words.iter().skip(confirmed.len()).map(|word| word.to_string()));
+    words.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Feed hypotheses in order, returning the confirmed text after each one
+    fn run(n: usize, hypotheses: &[&str]) -> Vec<String> {
+        let mut agreement = LocalAgreement::new(n);
+        hypotheses
+            .iter()
+            .map(|hypothesis| {
+                agreement.insert(hypothesis);
+                agreement.confirmed_text()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_growing_hypotheses_confirm_common_prefix() {
+        let confirmed = run(2, &[
+            "I",
+            "I think",
+            "I think we",
+            "I think we should go",
+            "I think we should go home",
+        ]);
+        assert_eq!(confirmed, vec![
+            "",
+            "I",
+            "I think",
+            "I think we",
+            "I think we should go",
+        ]);
+    }
+
+    #[test]
+    fn test_revised_words_wait_for_agreement() {
+        let confirmed = run(2, &[
+            "the cat",
+            "the cap sat",
+            "the cap sat on",
+            "the cap sat on the mat",
+        ]);
+        assert_eq!(confirmed, vec!["", "the", "the cap sat", "the cap sat on"]);
+    }
+
+    #[test]
+    fn test_higher_n_needs_more_agreement() {
+        let confirmed = run(3, &["hello there", "hello there", "hello their", "hello their"]);
+        assert_eq!(confirmed, vec!["", "", "hello", "hello"]);
+    }
+
+    #[test]
+    fn test_confirmed_words_are_never_revised() {
+        let mut agreement = LocalAgreement::new(2);
+        agreement.insert("one two");
+        agreement.insert("one two three");
+        assert_eq!(agreement.confirmed_text(), "one two");
+
+        // Later hypotheses change a confirmed word; the new agreement only adds words
+        agreement.insert("one too three four");
+        assert_eq!(agreement.insert("one too three four"), 2);
+        assert_eq!(agreement.confirmed_text(), "one two three four");
+
+        assert_eq!(agreement.take_confirmed().len(), 4);
+        assert!(agreement.confirmed_words().is_empty());
+    }
+
+    #[test]
+    fn test_reconcile_keeps_confirmed_words() {
+        let confirmed = vec!["this".to_string(), "is".to_string(), "a".to_string(), "test".to_string()];
+        assert_eq!(reconcile(&confirmed, "this is a test. Done."), "this is a test. Done.");
+        assert_eq!(reconcile(&confirmed, "This is the test"), "this is a test");
+        assert_eq!(reconcile(&confirmed, ""), "this is a test");
+        assert_eq!(reconcile(&[], "fresh text"), "fresh text");
+    }
+}
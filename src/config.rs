@@ -2,13 +2,45 @@ use anyhow::{Context, Result, bail};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
+use crate::commit_policy::CommitPolicy;
+use crate::hotkey::parse_hotkey;
+use crate::vad::VadKind;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
 pub struct Config {
     #[serde(default)]
     pub hotkeys: HotkeyConfig,
     #[serde(default)]
     pub transcription: TranscriptionConfig,
+    #[serde(default)]
+    pub output: OutputConfig,
+    #[serde(default)]
+    pub audio: AudioConfig,
+    /// Named alternatives to the transcription and output settings, each with a hotkey
+    #[serde(default)]
+    pub profiles: Vec<ProfileConfig>,
+}
+
+/// Settings for a kind of dictation (e.g. another language), started with its own hotkey
+///
+/// Settings that are not set are taken from the top-level configuration.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProfileConfig {
+    pub name: String,
+    /// Toggles a session with this profile
+    pub hotkey: String,
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub output_mode: Option<OutputMode>,
+    #[serde(default)]
+    pub silence_threshold: Option<f32>,
+    #[serde(default)]
+    pub adaptive_threshold: Option<bool>,
+    #[serde(default)]
+    pub commit_silence_ms: Option<u64>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -17,6 +49,18 @@ pub struct HotkeyConfig {
     pub start_transcription: String,
     #[serde(default = "default_stop_hotkey")]
     pub stop_transcription: String,
+    /// Hotkey that stops recording and erases everything typed in the session
+    #[serde(default)]
+    pub cancel_transcription: Option<String>,
+    /// Hotkey that pauses recording and resumes the same session
+    #[serde(default)]
+    pub pause_transcription: Option<String>,
+    /// Hotkey that records while held (in addition to start/stop)
+    #[serde(default)]
+    pub push_to_talk: Option<String>,
+    /// How long after releasing the push-to-talk hotkey recording stops
+    #[serde(default = "default_push_to_talk_grace_ms")]
+    pub push_to_talk_grace_ms: u64,
 }
 
 fn default_start_hotkey() -> String {
@@ -27,11 +71,19 @@ fn default_stop_hotkey() -> String {
     "Option+Space".to_string()
 }
 
+fn default_push_to_talk_grace_ms() -> u64 {
+    300 // The key is often released during the last word
+}
+
 impl Default for HotkeyConfig {
     fn default() -> Self {
         HotkeyConfig {
             start_transcription: default_start_hotkey(),
             stop_transcription: default_stop_hotkey(),
+            cancel_transcription: None,
+            pause_transcription: None,
+            push_to_talk: None,
+            push_to_talk_grace_ms: default_push_to_talk_grace_ms(),
         }
     }
 }
@@ -50,6 +102,32 @@ pub struct TranscriptionConfig {
     pub chunk_duration_ms: u64,
     #[serde(default = "default_silence_threshold")]
     pub silence_threshold: f32,
+    #[serde(default = "default_vad")]
+    pub vad: VadKind,
+    #[serde(default = "default_adaptive_threshold")]
+    pub adaptive_threshold: bool,
+    #[serde(default = "default_calibration_ms")]
+    pub calibration_ms: u64,
+    /// Silence after speech before the utterance is committed
+    #[serde(default = "default_commit_silence_ms")]
+    pub commit_silence_ms: u64,
+    /// Silence kept at the end of an utterance (more makes Whisper hallucinate)
+    #[serde(default = "default_trailing_silence_ms")]
+    pub trailing_silence_ms: u64,
+    /// Speech in an utterance before the first live preview
+    #[serde(default = "default_live_preview_delay_ms")]
+    pub live_preview_delay_ms: u64,
+    /// Shorter audio is padded with silence before it is transcribed
+    #[serde(default = "default_min_audio_ms")]
+    pub min_audio_ms: u64,
+    /// Smaller model used for the last attempt when an utterance keeps failing to transcribe
+    #[serde(default)]
+    pub fallback_model: Option<String>,
+    #[serde(default = "default_commit_policy")]
+    pub commit_policy: CommitPolicy,
+    /// Consecutive live hypotheses that must agree with the `local_agreement` policy
+    #[serde(default = "default_agreement_count")]
+    pub agreement_count: usize,
 }
 
 fn default_model() -> String {
@@ -76,6 +154,42 @@ fn default_silence_threshold() -> f32 {
     0.003 // RMS threshold for silence detection (more sensitive, picks up quieter speech)
 }
 
+fn default_vad() -> VadKind {
+    VadKind::Rms
+}
+
+fn default_adaptive_threshold() -> bool {
+    false // Use the fixed silence_threshold
+}
+
+fn default_calibration_ms() -> u64 {
+    300 // Ambient noise measured at the start of each recording
+}
+
+fn default_commit_silence_ms() -> u64 {
+    1500
+}
+
+fn default_trailing_silence_ms() -> u64 {
+    600 // Catches quiet word endings without inviting hallucinations
+}
+
+fn default_live_preview_delay_ms() -> u64 {
+    1500
+}
+
+fn default_min_audio_ms() -> u64 {
+    1500 // Whisper needs at least 1s of audio
+}
+
+fn default_commit_policy() -> CommitPolicy {
+    CommitPolicy::Silence
+}
+
+fn default_agreement_count() -> usize {
+    2 // LocalAgreement-2
+}
+
 impl Default for TranscriptionConfig {
     fn default() -> Self {
         TranscriptionConfig {
@@ -85,15 +199,121 @@ impl Default for TranscriptionConfig {
             streaming: default_streaming(),
             chunk_duration_ms: default_chunk_duration(),
             silence_threshold: default_silence_threshold(),
+            vad: default_vad(),
+            adaptive_threshold: default_adaptive_threshold(),
+            calibration_ms: default_calibration_ms(),
+            commit_silence_ms: default_commit_silence_ms(),
+            trailing_silence_ms: default_trailing_silence_ms(),
+            live_preview_delay_ms: default_live_preview_delay_ms(),
+            min_audio_ms: default_min_audio_ms(),
+            fallback_model: None,
+            commit_policy: default_commit_policy(),
+            agreement_count: default_agreement_count(),
         }
     }
 }
 
-impl Default for Config {
+impl TranscriptionConfig {
+    /// Number of audio chunks closest to a duration
+    pub fn chunks(&self, duration_ms: u64) -> usize {
+        ((duration_ms + self.chunk_duration_ms / 2) / self.chunk_duration_ms) as usize
+    }
+}
+
+/// Where transcribed text is sent
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputSinkKind {
+    /// Type into the focused application
+    Keyboard,
+    /// Print to stdout
+    Stdout,
+    /// Keep a file in sync with the transcription
+    File,
+}
+
+/// How the transcription is delivered
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputMode {
+    /// Type live into the output sink
+    Type,
+    /// Copy the committed text to the clipboard when recording stops
+    Clipboard,
+    /// Type live and copy to the clipboard when recording stops
+    Both,
+}
+
+impl OutputMode {
+    /// Whether text is sent to the output sink while transcribing
+    pub fn types(self) -> bool {
+        matches!(self, OutputMode::Type | OutputMode::Both)
+    }
+
+    /// Whether the committed text is copied to the clipboard at the end of a session
+    pub fn copies(self) -> bool {
+        matches!(self, OutputMode::Clipboard | OutputMode::Both)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OutputConfig {
+    #[serde(default = "default_output_mode")]
+    pub mode: OutputMode,
+    #[serde(default = "default_output_sink")]
+    pub sink: OutputSinkKind,
+    /// Output file for the `file` sink
+    #[serde(default)]
+    pub file: Option<PathBuf>,
+}
+
+fn default_output_mode() -> OutputMode {
+    OutputMode::Type
+}
+
+fn default_output_sink() -> OutputSinkKind {
+    OutputSinkKind::Keyboard
+}
+
+impl Default for OutputConfig {
     fn default() -> Self {
-        Config {
-            hotkeys: HotkeyConfig::default(),
-            transcription: TranscriptionConfig::default(),
+        OutputConfig {
+            mode: default_output_mode(),
+            sink: default_output_sink(),
+            file: None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AudioConfig {
+    /// Input device name, or part of it (see `live-transcribe list-devices`).
+    /// Falls back to the system default when not set or not connected.
+    #[serde(default)]
+    pub device: Option<String>,
+    /// Keep the microphone open between recordings so speech from just before the
+    /// hotkey is kept (the system shows the microphone as in use the whole time)
+    #[serde(default = "default_always_on")]
+    pub always_on: bool,
+    /// Audio kept from before a recording starts when `always_on` is enabled
+    #[serde(default = "default_pre_roll_ms")]
+    pub pre_roll_ms: u64,
+}
+
+fn default_always_on() -> bool {
+    false // Only open the microphone while recording
+}
+
+fn default_pre_roll_ms() -> u64 {
+    300
+}
+
+impl Default for AudioConfig {
+    fn default() -> Self {
+        AudioConfig {
+            device: None,
+            always_on: default_always_on(),
+            pre_roll_ms: default_pre_roll_ms(),
         }
     }
 }
@@ -129,6 +349,40 @@ impl Config {
         }
     }
 
+    /// Settings for a session with a profile (`None` = the top-level settings)
+    ///
+    /// The result has no profiles of its own.
+    pub fn for_profile(&self, profile: Option<usize>) -> Config {
+        let mut config = Config {
+            profiles: Vec::new(),
+            ..self.clone()
+        };
+        let Some(profile) = profile.and_then(|index| self.profiles.get(index)) else {
+            return config;
+        };
+
+        let transcription = &mut config.transcription;
+        if let Some(model) = &profile.model {
+            transcription.model = model.clone();
+        }
+        if let Some(language) = &profile.language {
+            transcription.language = language.clone();
+        }
+        if let Some(silence_threshold) = profile.silence_threshold {
+            transcription.silence_threshold = silence_threshold;
+        }
+        if let Some(adaptive_threshold) = profile.adaptive_threshold {
+            transcription.adaptive_threshold = adaptive_threshold;
+        }
+        if let Some(commit_silence_ms) = profile.commit_silence_ms {
+            transcription.commit_silence_ms = commit_silence_ms;
+        }
+        if let Some(output_mode) = profile.output_mode {
+            config.output.mode = output_mode;
+        }
+        config
+    }
+
     /// Validate configuration values
     pub fn validate(&self) -> Result<()> {
         // Validate chunk duration
@@ -147,10 +401,59 @@ impl Config {
             bail!("silence_threshold must be <= 1.0");
         }
 
+        // Validate noise floor calibration
+        if self.transcription.adaptive_threshold {
+            if self.transcription.vad != VadKind::Rms {
+                bail!("adaptive_threshold only works with vad: rms (the spectral detector has its own noise handling)");
+            }
+            if self.transcription.calibration_ms < 20 {
+                bail!("calibration_ms must be >= 20");
+            }
+            if self.transcription.calibration_ms > 5000 {
+                bail!("calibration_ms must be <= 5000 (5 seconds)");
+            }
+        }
+
+        // Validate streaming timing; durations are rounded to whole chunks, so compare chunk counts
+        let transcription = &self.transcription;
+        if transcription.chunks(transcription.commit_silence_ms) == 0 {
+            bail!("commit_silence_ms ({}) must be at least one chunk ({}ms)",
+                  transcription.commit_silence_ms, transcription.chunk_duration_ms);
+        }
+        if transcription.commit_silence_ms > 10000 {
+            bail!("commit_silence_ms must be <= 10000 (10 seconds)");
+        }
+        if transcription.chunks(transcription.trailing_silence_ms) >= transcription.chunks(transcription.commit_silence_ms) {
+            bail!("commit_silence_ms ({}) must exceed trailing_silence_ms ({}) by at least one chunk ({}ms)",
+                  transcription.commit_silence_ms, transcription.trailing_silence_ms, transcription.chunk_duration_ms);
+        }
+        if transcription.live_preview_delay_ms > 10000 {
+            bail!("live_preview_delay_ms must be <= 10000 (10 seconds)");
+        }
+        if transcription.min_audio_ms < 1000 {
+            bail!("min_audio_ms must be >= 1000 (Whisper rejects shorter audio)");
+        }
+        if transcription.min_audio_ms > 10000 {
+            bail!("min_audio_ms must be <= 10000 (10 seconds)");
+        }
+
+        // Validate commit policy
+        if self.transcription.commit_policy == CommitPolicy::LocalAgreement {
+            if self.transcription.agreement_count < 2 {
+                bail!("agreement_count must be >= 2 (1 would commit every live preview as-is)");
+            }
+            if self.transcription.agreement_count > 10 {
+                bail!("agreement_count must be <= 10");
+            }
+        }
+
         // Validate model name (basic check)
         if self.transcription.model.is_empty() {
             bail!("model name cannot be empty");
         }
+        if self.transcription.fallback_model.as_deref() == Some("") {
+            bail!("fallback_model cannot be empty (remove it to disable the fallback)");
+        }
 
         // Validate language code (basic check)
         if self.transcription.language.is_empty() {
@@ -165,7 +468,204 @@ impl Config {
             bail!("stop_transcription hotkey cannot be empty");
         }
 
+        // Parse every hotkey now, so a typo fails at load time rather than at registration.
+        // Optional hotkeys can't reuse another hotkey (start and stop may share one to toggle).
+        let parse = |name: &str, hotkey: &str| {
+            parse_hotkey(hotkey).with_context(|| format!("Invalid {} hotkey \"{}\"", name, hotkey))
+        };
+        let start = parse("start_transcription", &self.hotkeys.start_transcription)?;
+        let stop = parse("stop_transcription", &self.hotkeys.stop_transcription)?;
+        let mut assigned = vec![("start_transcription".to_string(), start)];
+        if stop != start {
+            assigned.push(("stop_transcription".to_string(), stop));
+        }
+        let optional = [
+            ("push_to_talk", &self.hotkeys.push_to_talk),
+            ("cancel_transcription", &self.hotkeys.cancel_transcription),
+            ("pause_transcription", &self.hotkeys.pause_transcription),
+        ];
+        for (name, hotkey_str) in optional {
+            let Some(hotkey_str) = hotkey_str else {
+                continue;
+            };
+            if hotkey_str.is_empty() {
+                bail!("{} hotkey cannot be empty (remove it to disable it)", name);
+            }
+            let hotkey = parse(name, hotkey_str)?;
+            if let Some((other, _)) = assigned.iter().find(|(_, assigned)| *assigned == hotkey) {
+                bail!("{} hotkey \"{}\" is already used by {}", name, hotkey_str, other);
+            }
+            assigned.push((name.to_string(), hotkey));
+        }
+        if self.hotkeys.push_to_talk_grace_ms > 2000 {
+            bail!("push_to_talk_grace_ms must be <= 2000 (2 seconds)");
+        }
+
+        // Validate profiles: unique names and hotkeys, and valid settings once applied
+        for (index, profile) in self.profiles.iter().enumerate() {
+            if profile.name.trim().is_empty() {
+                bail!("profile names cannot be empty");
+            }
+            if self.profiles[..index].iter().any(|other| other.name == profile.name) {
+                bail!("profile name \"{}\" is used more than once", profile.name);
+            }
+            let name = format!("profile \"{}\"", profile.name);
+            let hotkey = parse(&name, &profile.hotkey)?;
+            if let Some((other, _)) = assigned.iter().find(|(_, assigned)| *assigned == hotkey) {
+                bail!("{} hotkey \"{}\" is already used by {}", name, profile.hotkey, other);
+            }
+            assigned.push((name, hotkey));
+
+            self.for_profile(Some(index))
+                .validate()
+                .with_context(|| format!("Invalid settings in profile \"{}\"", profile.name))?;
+        }
+
+        // Validate pre-roll
+        if self.audio.always_on {
+            if self.audio.pre_roll_ms == 0 {
+                bail!("pre_roll_ms must be greater than 0 when always_on is enabled");
+            }
+            if self.audio.pre_roll_ms > 2000 {
+                bail!("pre_roll_ms must be <= 2000 (2 seconds)");
+            }
+        }
+
+        // Validate output sink
+        if self.output.sink == OutputSinkKind::File && self.output.file.is_none() {
+            bail!("output.file must be set when output.sink is \"file\"");
+        }
+
         Ok(())
     }
 
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_timing(chunk_duration_ms: u64, commit_silence_ms: u64, trailing_silence_ms: u64) -> Config {
+        let mut config = Config::default();
+        config.transcription.chunk_duration_ms = chunk_duration_ms;
+        config.transcription.commit_silence_ms = commit_silence_ms;
+        config.transcription.trailing_silence_ms = trailing_silence_ms;
+        config
+    }
+
+    #[test]
+    fn test_default_timing_is_valid() {
+        assert!(Config::default().validate().is_ok());
+        let transcription = TranscriptionConfig::default();
+        assert_eq!(transcription.chunks(transcription.commit_silence_ms), 5);
+        assert_eq!(transcription.chunks(transcription.trailing_silence_ms), 2);
+    }
+
+    #[test]
+    fn test_optional_hotkeys_must_be_unique() {
+        let mut config = Config::default();
+        config.hotkeys.pause_transcription = Some("Cmd+Shift+P".to_string());
+        config.hotkeys.cancel_transcription = Some("Cmd+Shift+X".to_string());
+        assert!(config.validate().is_ok());
+
+        config.hotkeys.cancel_transcription = Some("Cmd+Shift+P".to_string());
+        let error = config.validate().unwrap_err().to_string();
+        assert!(error.contains("\"Cmd+Shift+P\" is already used"), "{}", error);
+
+        // The same key combination written differently
+        config.hotkeys.cancel_transcription = Some("shift + option + SPACE".to_string());
+        config.hotkeys.start_transcription = "Option+Shift+Space".to_string();
+        config.hotkeys.stop_transcription = "Option+Shift+Space".to_string();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_hotkey_typo_fails_validation() {
+        let mut config = Config::default();
+        config.hotkeys.start_transcription = "Cmd+Shfit+T".to_string();
+        let error = format!("{:#}", config.validate().unwrap_err());
+        assert!(error.contains("Invalid start_transcription hotkey"), "{}", error);
+        assert!(error.contains("did you mean \"Shift\""), "{}", error);
+    }
+
+    #[test]
+    fn test_commit_silence_must_exceed_trailing_silence() {
+        assert!(with_timing(300, 600, 600).validate().is_err());
+        assert!(with_timing(100, 700, 600).validate().is_ok());
+        // Both round to 2 chunks of 300ms
+        assert!(with_timing(300, 700, 600).validate().is_err());
+        // Less than half a chunk rounds to no silence at all
+        assert!(with_timing(1000, 400, 0).validate().is_err());
+    }
+
+    #[test]
+    fn test_adaptive_threshold_requires_rms_vad() {
+        let mut config = Config::default();
+        config.transcription.vad = VadKind::Spectral;
+        assert!(config.validate().is_ok());
+
+        config.transcription.adaptive_threshold = true;
+        let error = config.validate().unwrap_err().to_string();
+        assert!(error.contains("adaptive_threshold only works with vad: rms"), "{}", error);
+
+        // The same combination through a profile
+        config.transcription.adaptive_threshold = false;
+        let mut quiet = profile("Quiet", "Option+Q");
+        quiet.adaptive_threshold = Some(true);
+        config.profiles.push(quiet);
+        assert!(config.validate().is_err());
+    }
+
+    fn profile(name: &str, hotkey: &str) -> ProfileConfig {
+        ProfileConfig {
+            name: name.to_string(),
+            hotkey: hotkey.to_string(),
+            model: None,
+            language: None,
+            output_mode: None,
+            silence_threshold: None,
+            adaptive_threshold: None,
+            commit_silence_ms: None,
+        }
+    }
+
+    #[test]
+    fn test_profile_overrides_top_level_settings() {
+        let mut config = Config::default();
+        let mut dutch = profile("Dutch", "Option+D");
+        dutch.model = Some("medium".to_string());
+        dutch.language = Some("nl".to_string());
+        dutch.output_mode = Some(OutputMode::Clipboard);
+        config.profiles.push(dutch);
+        assert!(config.validate().is_ok());
+
+        let applied = config.for_profile(Some(0));
+        assert_eq!(applied.transcription.model, "medium");
+        assert_eq!(applied.transcription.language, "nl");
+        assert_eq!(applied.output.mode, OutputMode::Clipboard);
+        assert_eq!(applied.transcription.silence_threshold, config.transcription.silence_threshold);
+        assert!(applied.profiles.is_empty());
+
+        let top_level = config.for_profile(None);
+        assert_eq!(top_level.transcription.model, config.transcription.model);
+        assert_eq!(top_level.transcription.language, config.transcription.language);
+    }
+
+    #[test]
+    fn test_profiles_are_validated() {
+        let mut config = Config::default();
+        config.profiles.push(profile("Dutch", "Option+D"));
+        config.profiles.push(profile("Dutch", "Option+E"));
+        assert!(config.validate().is_err());
+
+        config.profiles[1].name = "Notes".to_string();
+        config.profiles[1].hotkey = "Option+Space".to_string();
+        let error = config.validate().unwrap_err().to_string();
+        assert!(error.contains("already used by start_transcription"), "{}", error);
+
+        config.profiles[1].hotkey = "Option+E".to_string();
+        config.profiles[1].commit_silence_ms = Some(20000);
+        let error = format!("{:#}", config.validate().unwrap_err());
+        assert!(error.contains("profile \"Notes\""), "{}", error);
+    }
+}
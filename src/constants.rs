@@ -1,29 +1,52 @@
 /// Application-wide constants for audio processing, transcription, and keyboard handling
-
-pub mod audio {
-    /// Minimum audio samples required for Whisper transcription (1.5 seconds)
-    pub const MIN_WHISPER_SAMPLES: usize = 24000; // 1.5s at 16kHz
-}
+///
+/// Timings users may want to tune (silence before a commit, live preview delay, minimum
+/// audio length) are in `TranscriptionConfig` instead, in milliseconds.
 
 pub mod vad {
-    /// Number of consecutive silence chunks required to commit VAD transcription
-    /// At 300ms per chunk, 5 chunks = 1.5 seconds of silence
-    pub const COMMIT_SILENCE_CHUNKS: usize = 5;
-
-    /// Maximum number of trailing silence chunks to include in VAD buffer
-    /// This prevents hallucinations while catching quiet speech
-    /// At 300ms per chunk, 2 chunks = 600ms of trailing silence
-    pub const MAX_TRAILING_SILENCE_CHUNKS: usize = 2;
+    /// Longest utterance transcribed in one piece (Whisper's window is 30 seconds)
+    /// Continuous speech is split at the quietest point shortly before this limit
+    pub const MAX_UTTERANCE_SAMPLES: usize = 25 * 16000;
+
+    /// How far before the limit to look for a quiet split point (5 seconds)
+    pub const SPLIT_SEARCH_SAMPLES: usize = 5 * 16000;
+
+    /// Frame size used to find the quietest split point (20ms)
+    pub const SPLIT_FRAME_SAMPLES: usize = 320;
+
+    /// Audio before the split repeated at the start of the next part (500ms)
+    /// A word on the boundary is complete in one of the parts; repeated words are removed
+    pub const SPLIT_OVERLAP_SAMPLES: usize = 8000;
 }
 
 pub mod streaming {
-    /// Number of chunks to accumulate before triggering live preview
-    /// At 300ms per chunk, 5 chunks = 1.5 seconds
-    pub const LIVE_PREVIEW_DELAY_CHUNKS: usize = 5;
+    /// Longest tail of the VAD buffer transcribed for a live preview (10 seconds)
+    /// Earlier audio keeps the text of its last preview instead of being re-transcribed
+    pub const LIVE_PREVIEW_MAX_SAMPLES: usize = 10 * 16000;
+
+    /// Audio repeated at the start of the next live preview window (1 second)
+    pub const LIVE_PREVIEW_OVERLAP_SAMPLES: usize = 16000;
+}
+
+pub mod retry {
+    /// Transcription attempts per utterance before it is dropped
+    pub const MAX_ATTEMPTS: u32 = 3;
+
+    /// Delay before the first retry, doubled for each further retry
+    pub const INITIAL_BACKOFF_MS: u64 = 500;
+
+    /// Upper bound for the retry delay
+    pub const MAX_BACKOFF_MS: u64 = 4000;
+}
+
+pub mod session {
+    /// Time after recording stops before a missing transcription is reported as stalled
+    /// The session keeps waiting: long non-streaming recordings can take a while on slower models
+    pub const FINALIZE_STALL_WARNING_MS: u64 = 30_000;
 }
 
 pub mod worker {
-    /// Maximum number of pending transcription requests in queue
-    /// This prevents unbounded memory growth under load
+    /// Maximum number of pending live preview requests in queue
+    /// Stale previews are dropped instead of piling up (VAD commits are never dropped)
     pub const MAX_PENDING_REQUESTS: usize = 2;
 }
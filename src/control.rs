@@ -0,0 +1,107 @@
+//! Commands for the running app, sent from the command line
+//!
+//! The app is sandboxed without network access, which also rules out local sockets, so
+//! commands go through an inbox directory in the config folder: the CLI writes each one to
+//! its own file, and the app picks them up in order and deletes them.
+
+use anyhow::{bail, Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::config::Config;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ControlCommand {
+    /// Load another Whisper model in the background and switch to it between sessions
+    SwitchModel(String),
+}
+
+impl ControlCommand {
+    fn parse(line: &str) -> Result<Self> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words.as_slice() {
+            ["switch-model", model] => Ok(ControlCommand::SwitchModel(model.to_string())),
+            _ => bail!("Unknown command \"{}\"", line.trim()),
+        }
+    }
+
+    fn to_line(&self) -> String {
+        match self {
+            ControlCommand::SwitchModel(model) => format!("switch-model {}", model),
+        }
+    }
+}
+
+/// Directory the running app reads commands from
+pub fn inbox_dir() -> Result<PathBuf> {
+    Ok(Config::config_dir()?.join("control"))
+}
+
+/// Leave a command in the inbox for the running app
+pub fn send(inbox: &Path, command: &ControlCommand) -> Result<()> {
+    fs::create_dir_all(inbox).context("Failed to create control directory")?;
+
+    // Names sort in the order the commands were sent
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_nanos())
+        .unwrap_or_default();
+    let name = format!("{:020}-{}", nanos, std::process::id());
+
+    // Written under another name first, so the app never reads a half-written command
+    let temp_path = inbox.join(format!("{}.tmp", name));
+    fs::write(&temp_path, command.to_line()).context("Failed to write command")?;
+    fs::rename(&temp_path, inbox.join(format!("{}.cmd", name)))
+        .context("Failed to send command")?;
+    Ok(())
+}
+
+/// Take the commands waiting in the inbox, oldest first
+///
+/// Command files are deleted once read. Unknown commands are reported and dropped.
+pub fn receive(inbox: &Path) -> Vec<ControlCommand> {
+    let Ok(entries) = fs::read_dir(inbox) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|extension| extension == "cmd"))
+        .collect();
+    paths.sort();
+
+    let mut commands = Vec::new();
+    for path in paths {
+        let contents = fs::read_to_string(&path);
+        let _ = fs::remove_file(&path);
+        match contents.map_err(anyhow::Error::from).and_then(|line| ControlCommand::parse(&line)) {
+            Ok(command) => commands.push(command),
+            Err(e) => eprintln!("⚠️  Ignoring control command {}: {:#}", path.display(), e),
+        }
+    }
+    commands
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commands_are_received_in_order_once() {
+        let inbox = std::env::temp_dir()
+            .join(format!("live-transcribe-control-{}", std::process::id()));
+
+        send(&inbox, &ControlCommand::SwitchModel("small.en".to_string())).unwrap();
+        send(&inbox, &ControlCommand::SwitchModel("medium".to_string())).unwrap();
+        fs::write(inbox.join("0-0.cmd"), "reload everything").unwrap();
+
+        assert_eq!(
+            receive(&inbox),
+            vec![
+                ControlCommand::SwitchModel("small.en".to_string()),
+                ControlCommand::SwitchModel("medium".to_string()),
+            ]
+        );
+        assert!(receive(&inbox).is_empty());
+        let _ = fs::remove_dir_all(&inbox);
+    }
+}
@@ -1,15 +1,26 @@
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use global_hotkey::{
     hotkey::{Code, HotKey, Modifiers},
-    GlobalHotKeyEvent, GlobalHotKeyManager,
+    GlobalHotKeyEvent, GlobalHotKeyManager, HotKeyState,
 };
-use crate::config::HotkeyConfig;
+use std::time::{Duration, Instant};
+use crate::config::{HotkeyConfig, ProfileConfig};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum HotkeyEvent {
     StartTranscription,
     StopTranscription,
     ToggleTranscription,
+    /// The push-to-talk hotkey went down
+    PushToTalkPressed,
+    /// The push-to-talk hotkey was released
+    PushToTalkReleased,
+    /// Discard the session and erase its text
+    CancelTranscription,
+    /// Pause recording, or resume a paused session
+    PauseTranscription,
+    /// Toggle a session with the profile at this index
+    ToggleProfile(usize),
 }
 
 pub struct HotkeyManager {
@@ -17,19 +28,380 @@ pub struct HotkeyManager {
     start_hotkey: Option<HotKey>,
     stop_hotkey: Option<HotKey>,
     toggle_hotkey: Option<HotKey>,
+    push_to_talk_hotkey: Option<HotKey>,
+    cancel_hotkey: Option<HotKey>,
+    pause_hotkey: Option<HotKey>,
+    /// One per profile, in the order of the profiles
+    profile_hotkeys: Vec<HotKey>,
+}
+
+/// Push-to-talk: recording runs while the hotkey is held
+///
+/// People tend to let go of the key while saying the last word, so the recording only
+/// stops once the key has been released for the grace period. Pressing it again within
+/// the grace period continues the same recording.
+pub struct PushToTalk {
+    grace: Duration,
+    held: bool,
+    released_at: Option<Instant>,
+}
+
+impl PushToTalk {
+    pub fn new(grace: Duration) -> Self {
+        Self {
+            grace,
+            held: false,
+            released_at: None,
+        }
+    }
+
+    /// The hotkey went down; returns true if this starts a new recording
+    ///
+    /// Push-to-talk only controls recordings it started: with another session running
+    /// (`session_running`), the press is ignored, so releasing the key won't stop it.
+    pub fn press(&mut self, session_running: bool) -> bool {
+        let continues = self.held || self.released_at.is_some();
+        if !continues && session_running {
+            return false;
+        }
+        self.held = true;
+        self.released_at = None;
+        !continues
+    }
+
+    /// Forget the recording push-to-talk started (it was stopped or cancelled another way)
+    pub fn reset(&mut self) {
+        self.held = false;
+        self.released_at = None;
+    }
+
+    /// The hotkey was released; the recording stops after the grace period
+    pub fn release(&mut self, now: Instant) {
+        if self.held {
+            self.held = false;
+            self.released_at = Some(now);
+        }
+    }
+
+    /// Returns true once when the grace period after a release has passed
+    pub fn poll_stop(&mut self, now: Instant) -> bool {
+        match self.released_at {
+            Some(at) if now.duration_since(at) >= self.grace => {
+                self.released_at = None;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Key names accepted in hotkey strings (case-insensitive), with their key codes
+///
+/// Every key has its `Code` name (`KeyA`, `ArrowUp`, `NumpadAdd`); most also have a
+/// shorter alias (`A`, `Up`, `NumpadPlus`). `+` separates keys, so use `NumpadAdd` or
+/// `Equal` for the plus key.
+const KEY_NAMES: &[(&str, Code)] = &[
+    ("A", Code::KeyA),
+    ("KeyA", Code::KeyA),
+    ("B", Code::KeyB),
+    ("KeyB", Code::KeyB),
+    ("C", Code::KeyC),
+    ("KeyC", Code::KeyC),
+    ("D", Code::KeyD),
+    ("KeyD", Code::KeyD),
+    ("E", Code::KeyE),
+    ("KeyE", Code::KeyE),
+    ("F", Code::KeyF),
+    ("KeyF", Code::KeyF),
+    ("G", Code::KeyG),
+    ("KeyG", Code::KeyG),
+    ("H", Code::KeyH),
+    ("KeyH", Code::KeyH),
+    ("I", Code::KeyI),
+    ("KeyI", Code::KeyI),
+    ("J", Code::KeyJ),
+    ("KeyJ", Code::KeyJ),
+    ("K", Code::KeyK),
+    ("KeyK", Code::KeyK),
+    ("L", Code::KeyL),
+    ("KeyL", Code::KeyL),
+    ("M", Code::KeyM),
+    ("KeyM", Code::KeyM),
+    ("N", Code::KeyN),
+    ("KeyN", Code::KeyN),
+    ("O", Code::KeyO),
+    ("KeyO", Code::KeyO),
+    ("P", Code::KeyP),
+    ("KeyP", Code::KeyP),
+    ("Q", Code::KeyQ),
+    ("KeyQ", Code::KeyQ),
+    ("R", Code::KeyR),
+    ("KeyR", Code::KeyR),
+    ("S", Code::KeyS),
+    ("KeyS", Code::KeyS),
+    ("T", Code::KeyT),
+    ("KeyT", Code::KeyT),
+    ("U", Code::KeyU),
+    ("KeyU", Code::KeyU),
+    ("V", Code::KeyV),
+    ("KeyV", Code::KeyV),
+    ("W", Code::KeyW),
+    ("KeyW", Code::KeyW),
+    ("X", Code::KeyX),
+    ("KeyX", Code::KeyX),
+    ("Y", Code::KeyY),
+    ("KeyY", Code::KeyY),
+    ("Z", Code::KeyZ),
+    ("KeyZ", Code::KeyZ),
+    ("0", Code::Digit0),
+    ("Digit0", Code::Digit0),
+    ("1", Code::Digit1),
+    ("Digit1", Code::Digit1),
+    ("2", Code::Digit2),
+    ("Digit2", Code::Digit2),
+    ("3", Code::Digit3),
+    ("Digit3", Code::Digit3),
+    ("4", Code::Digit4),
+    ("Digit4", Code::Digit4),
+    ("5", Code::Digit5),
+    ("Digit5", Code::Digit5),
+    ("6", Code::Digit6),
+    ("Digit6", Code::Digit6),
+    ("7", Code::Digit7),
+    ("Digit7", Code::Digit7),
+    ("8", Code::Digit8),
+    ("Digit8", Code::Digit8),
+    ("9", Code::Digit9),
+    ("Digit9", Code::Digit9),
+    ("F1", Code::F1),
+    ("F2", Code::F2),
+    ("F3", Code::F3),
+    ("F4", Code::F4),
+    ("F5", Code::F5),
+    ("F6", Code::F6),
+    ("F7", Code::F7),
+    ("F8", Code::F8),
+    ("F9", Code::F9),
+    ("F10", Code::F10),
+    ("F11", Code::F11),
+    ("F12", Code::F12),
+    ("F13", Code::F13),
+    ("F14", Code::F14),
+    ("F15", Code::F15),
+    ("F16", Code::F16),
+    ("F17", Code::F17),
+    ("F18", Code::F18),
+    ("F19", Code::F19),
+    ("F20", Code::F20),
+    ("F21", Code::F21),
+    ("F22", Code::F22),
+    ("F23", Code::F23),
+    ("F24", Code::F24),
+    ("Space", Code::Space),
+    ("Enter", Code::Enter),
+    ("Return", Code::Enter),
+    ("Tab", Code::Tab),
+    ("Backspace", Code::Backspace),
+    ("Escape", Code::Escape),
+    ("Esc", Code::Escape),
+    ("Delete", Code::Delete),
+    ("Del", Code::Delete),
+    ("ForwardDelete", Code::Delete),
+    ("Insert", Code::Insert),
+    ("Ins", Code::Insert),
+    ("Home", Code::Home),
+    ("End", Code::End),
+    ("PageUp", Code::PageUp),
+    ("PgUp", Code::PageUp),
+    ("PageDown", Code::PageDown),
+    ("PgDn", Code::PageDown),
+    ("Up", Code::ArrowUp),
+    ("ArrowUp", Code::ArrowUp),
+    ("Down", Code::ArrowDown),
+    ("ArrowDown", Code::ArrowDown),
+    ("Left", Code::ArrowLeft),
+    ("ArrowLeft", Code::ArrowLeft),
+    ("Right", Code::ArrowRight),
+    ("ArrowRight", Code::ArrowRight),
+    ("CapsLock", Code::CapsLock),
+    ("NumLock", Code::NumLock),
+    ("ScrollLock", Code::ScrollLock),
+    ("PrintScreen", Code::PrintScreen),
+    ("PrtSc", Code::PrintScreen),
+    ("Pause", Code::Pause),
+    ("ContextMenu", Code::ContextMenu),
+    ("Menu", Code::ContextMenu),
+    ("Help", Code::Help),
+    ("-", Code::Minus),
+    ("Minus", Code::Minus),
+    ("=", Code::Equal),
+    ("Equal", Code::Equal),
+    ("[", Code::BracketLeft),
+    ("BracketLeft", Code::BracketLeft),
+    ("]", Code::BracketRight),
+    ("BracketRight", Code::BracketRight),
+    ("\\", Code::Backslash),
+    ("Backslash", Code::Backslash),
+    ("IntlBackslash", Code::IntlBackslash),
+    ("Section", Code::IntlBackslash),
+    (";", Code::Semicolon),
+    ("Semicolon", Code::Semicolon),
+    ("'", Code::Quote),
+    ("Quote", Code::Quote),
+    ("`", Code::Backquote),
+    ("Backquote", Code::Backquote),
+    ("Grave", Code::Backquote),
+    (",", Code::Comma),
+    ("Comma", Code::Comma),
+    (".", Code::Period),
+    ("Period", Code::Period),
+    ("/", Code::Slash),
+    ("Slash", Code::Slash),
+    ("Numpad0", Code::Numpad0),
+    ("Num0", Code::Numpad0),
+    ("Numpad1", Code::Numpad1),
+    ("Num1", Code::Numpad1),
+    ("Numpad2", Code::Numpad2),
+    ("Num2", Code::Numpad2),
+    ("Numpad3", Code::Numpad3),
+    ("Num3", Code::Numpad3),
+    ("Numpad4", Code::Numpad4),
+    ("Num4", Code::Numpad4),
+    ("Numpad5", Code::Numpad5),
+    ("Num5", Code::Numpad5),
+    ("Numpad6", Code::Numpad6),
+    ("Num6", Code::Numpad6),
+    ("Numpad7", Code::Numpad7),
+    ("Num7", Code::Numpad7),
+    ("Numpad8", Code::Numpad8),
+    ("Num8", Code::Numpad8),
+    ("Numpad9", Code::Numpad9),
+    ("Num9", Code::Numpad9),
+    ("NumpadAdd", Code::NumpadAdd),
+    ("NumpadPlus", Code::NumpadAdd),
+    ("NumpadSubtract", Code::NumpadSubtract),
+    ("NumpadMinus", Code::NumpadSubtract),
+    ("NumpadMultiply", Code::NumpadMultiply),
+    ("NumpadDivide", Code::NumpadDivide),
+    ("NumpadDecimal", Code::NumpadDecimal),
+    ("NumpadEnter", Code::NumpadEnter),
+    ("NumpadEqual", Code::NumpadEqual),
+    ("NumpadComma", Code::NumpadComma),
+    ("NumpadClear", Code::NumpadClear),
+    ("MediaPlayPause", Code::MediaPlayPause),
+    ("PlayPause", Code::MediaPlayPause),
+    ("MediaStop", Code::MediaStop),
+    ("MediaTrackNext", Code::MediaTrackNext),
+    ("NextTrack", Code::MediaTrackNext),
+    ("MediaTrackPrevious", Code::MediaTrackPrevious),
+    ("PrevTrack", Code::MediaTrackPrevious),
+    ("AudioVolumeUp", Code::AudioVolumeUp),
+    ("VolumeUp", Code::AudioVolumeUp),
+    ("AudioVolumeDown", Code::AudioVolumeDown),
+    ("VolumeDown", Code::AudioVolumeDown),
+    ("AudioVolumeMute", Code::AudioVolumeMute),
+    ("VolumeMute", Code::AudioVolumeMute),
+    ("Mute", Code::AudioVolumeMute),
+];
+
+/// Modifier names accepted in hotkey strings (case-insensitive)
+const MODIFIER_NAMES: &[(&str, Modifiers)] = &[
+    ("Cmd", Modifiers::SUPER),
+    ("Command", Modifiers::SUPER),
+    ("Super", Modifiers::SUPER),
+    ("Ctrl", Modifiers::CONTROL),
+    ("Control", Modifiers::CONTROL),
+    ("Alt", Modifiers::ALT),
+    ("Option", Modifiers::ALT),
+    ("Opt", Modifiers::ALT),
+    ("Shift", Modifiers::SHIFT),
+];
+
+/// Parse a hotkey string like `"Cmd+Shift+T"`: any modifiers and exactly one key
+///
+/// Unknown names fail with a suggestion for the closest known name.
+pub fn parse_hotkey(hotkey_str: &str) -> Result<HotKey> {
+    if hotkey_str.trim().is_empty() {
+        bail!("Hotkey string is empty");
+    }
+
+    let mut modifiers = Modifiers::empty();
+    let mut key_code = None;
+
+    for part in hotkey_str.split('+').map(|s| s.trim()) {
+        if part.is_empty() {
+            bail!("Empty key name in \"{}\" (use NumpadAdd or Equal for the plus key)", hotkey_str);
+        }
+        if let Some((_, modifier)) = MODIFIER_NAMES.iter().find(|(name, _)| name.eq_ignore_ascii_case(part)) {
+            modifiers |= *modifier;
+            continue;
+        }
+        // Parse the actual key
+        let code = parse_key_code(part)?;
+        if key_code.replace(code).is_some() {
+            bail!("More than one key in \"{}\" (only modifiers can be combined)", hotkey_str);
+        }
+    }
+
+    let code = key_code.with_context(|| format!("No key in \"{}\", only modifiers", hotkey_str))?;
+    Ok(HotKey::new(Some(modifiers), code))
+}
+
+/// Look up a key by name or alias (case-insensitive)
+pub fn parse_key_code(key: &str) -> Result<Code> {
+    if let Some((_, code)) = KEY_NAMES.iter().find(|(name, _)| name.eq_ignore_ascii_case(key)) {
+        return Ok(*code);
+    }
+
+    match suggest_name(key) {
+        Some(suggestion) => bail!("Unknown key \"{}\" (did you mean \"{}\"?)", key, suggestion),
+        None => bail!("Unknown key \"{}\"", key),
+    }
+}
+
+/// The known key or modifier name closest to a misspelled one, if any is close enough
+fn suggest_name(unknown: &str) -> Option<&'static str> {
+    let unknown = unknown.to_lowercase();
+    let max_distance = unknown.chars().count() / 3 + 1;
+
+    KEY_NAMES
+        .iter()
+        .map(|(name, _)| *name)
+        .chain(MODIFIER_NAMES.iter().map(|(name, _)| *name))
+        .map(|name| (levenshtein(&unknown, &name.to_lowercase()), name))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, name)| name)
+}
+
+/// Edit distance between two strings (insertions, deletions and substitutions)
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
 }
 
 impl HotkeyManager {
-    pub fn new(config: &HotkeyConfig) -> Result<Self> {
+    pub fn new(config: &HotkeyConfig, profiles: &[ProfileConfig]) -> Result<Self> {
         let manager = GlobalHotKeyManager::new()
             .context("Failed to create global hotkey manager")?;
 
         // Check if using toggle mode (same hotkey for start and stop)
-        let use_toggle = config.start_transcription == config.stop_transcription;
+        let use_toggle = parse_hotkey(&config.start_transcription).ok() == parse_hotkey(&config.stop_transcription).ok();
 
         let (start_hotkey, stop_hotkey, toggle_hotkey) = if use_toggle {
             // Toggle mode: single hotkey
-            let hotkey = Self::parse_hotkey(&config.start_transcription)
+            let hotkey = parse_hotkey(&config.start_transcription)
                 .context("Failed to parse toggle hotkey")?;
             manager.register(hotkey)
                 .context("Failed to register toggle hotkey")?;
@@ -40,12 +412,12 @@ impl HotkeyManager {
             (None, None, Some(hotkey))
         } else {
             // Separate mode: different hotkeys for start and stop
-            let start = Self::parse_hotkey(&config.start_transcription)
+            let start = parse_hotkey(&config.start_transcription)
                 .context("Failed to parse start transcription hotkey")?;
             manager.register(start)
                 .context("Failed to register start transcription hotkey")?;
 
-            let stop = Self::parse_hotkey(&config.stop_transcription)
+            let stop = parse_hotkey(&config.stop_transcription)
                 .context("Failed to parse stop transcription hotkey")?;
             manager.register(stop)
                 .context("Failed to register stop transcription hotkey")?;
@@ -57,102 +429,80 @@ impl HotkeyManager {
             (Some(start), Some(stop), None)
         };
 
+        // Push-to-talk: an additional hotkey that records while held
+        let push_to_talk_hotkey = match &config.push_to_talk {
+            Some(hotkey_str) => {
+                let hotkey = parse_hotkey(hotkey_str)
+                    .context("Failed to parse push-to-talk hotkey")?;
+                manager.register(hotkey)
+                    .context("Failed to register push-to-talk hotkey")?;
+                println!("  Push-to-talk (hold): {}", hotkey_str);
+                Some(hotkey)
+            }
+            None => None,
+        };
+
+        let cancel_hotkey = match &config.cancel_transcription {
+            Some(hotkey_str) => {
+                let hotkey = parse_hotkey(hotkey_str)
+                    .context("Failed to parse cancel transcription hotkey")?;
+                manager.register(hotkey)
+                    .context("Failed to register cancel transcription hotkey")?;
+                println!("  Cancel: {}", hotkey_str);
+                Some(hotkey)
+            }
+            None => None,
+        };
+
+        let pause_hotkey = match &config.pause_transcription {
+            Some(hotkey_str) => {
+                let hotkey = parse_hotkey(hotkey_str)
+                    .context("Failed to parse pause transcription hotkey")?;
+                manager.register(hotkey)
+                    .context("Failed to register pause transcription hotkey")?;
+                println!("  Pause/resume: {}", hotkey_str);
+                Some(hotkey)
+            }
+            None => None,
+        };
+
+        let mut profile_hotkeys = Vec::with_capacity(profiles.len());
+        for profile in profiles {
+            let hotkey = parse_hotkey(&profile.hotkey)
+                .with_context(|| format!("Failed to parse hotkey for profile \"{}\"", profile.name))?;
+            manager.register(hotkey)
+                .with_context(|| format!("Failed to register hotkey for profile \"{}\"", profile.name))?;
+            println!("  Profile \"{}\": {}", profile.name, profile.hotkey);
+            profile_hotkeys.push(hotkey);
+        }
+
         Ok(HotkeyManager {
             manager,
             start_hotkey,
             stop_hotkey,
             toggle_hotkey,
+            push_to_talk_hotkey,
+            cancel_hotkey,
+            pause_hotkey,
+            profile_hotkeys,
         })
     }
 
-    fn parse_hotkey(hotkey_str: &str) -> Result<HotKey> {
-        let parts: Vec<&str> = hotkey_str.split('+').map(|s| s.trim()).collect();
-
-        if parts.is_empty() {
-            anyhow::bail!("Hotkey string is empty");
-        }
-
-        let mut modifiers = Modifiers::empty();
-        let mut key_code = None;
-
-        for part in parts {
-            match part.to_lowercase().as_str() {
-                "cmd" | "command" | "super" => modifiers |= Modifiers::SUPER,
-                "ctrl" | "control" => modifiers |= Modifiers::CONTROL,
-                "alt" | "option" => modifiers |= Modifiers::ALT,
-                "shift" => modifiers |= Modifiers::SHIFT,
-                // Parse the actual key
-                key => {
-                    key_code = Some(Self::parse_key_code(key)?);
+    pub fn poll_event(&self) -> Option<HotkeyEvent> {
+        if let Ok(event) = GlobalHotKeyEvent::receiver().try_recv() {
+            if let Some(push_to_talk) = &self.push_to_talk_hotkey {
+                if event.id == push_to_talk.id() {
+                    return Some(match event.state {
+                        HotKeyState::Pressed => HotkeyEvent::PushToTalkPressed,
+                        HotKeyState::Released => HotkeyEvent::PushToTalkReleased,
+                    });
                 }
             }
-        }
-
-        let code = key_code.context("No key code found in hotkey string")?;
-        Ok(HotKey::new(Some(modifiers), code))
-    }
-
-    fn parse_key_code(key: &str) -> Result<Code> {
-        match key.to_uppercase().as_str() {
-            "A" => Ok(Code::KeyA),
-            "B" => Ok(Code::KeyB),
-            "C" => Ok(Code::KeyC),
-            "D" => Ok(Code::KeyD),
-            "E" => Ok(Code::KeyE),
-            "F" => Ok(Code::KeyF),
-            "G" => Ok(Code::KeyG),
-            "H" => Ok(Code::KeyH),
-            "I" => Ok(Code::KeyI),
-            "J" => Ok(Code::KeyJ),
-            "K" => Ok(Code::KeyK),
-            "L" => Ok(Code::KeyL),
-            "M" => Ok(Code::KeyM),
-            "N" => Ok(Code::KeyN),
-            "O" => Ok(Code::KeyO),
-            "P" => Ok(Code::KeyP),
-            "Q" => Ok(Code::KeyQ),
-            "R" => Ok(Code::KeyR),
-            "S" => Ok(Code::KeyS),
-            "T" => Ok(Code::KeyT),
-            "U" => Ok(Code::KeyU),
-            "V" => Ok(Code::KeyV),
-            "W" => Ok(Code::KeyW),
-            "X" => Ok(Code::KeyX),
-            "Y" => Ok(Code::KeyY),
-            "Z" => Ok(Code::KeyZ),
-            "0" => Ok(Code::Digit0),
-            "1" => Ok(Code::Digit1),
-            "2" => Ok(Code::Digit2),
-            "3" => Ok(Code::Digit3),
-            "4" => Ok(Code::Digit4),
-            "5" => Ok(Code::Digit5),
-            "6" => Ok(Code::Digit6),
-            "7" => Ok(Code::Digit7),
-            "8" => Ok(Code::Digit8),
-            "9" => Ok(Code::Digit9),
-            "F1" => Ok(Code::F1),
-            "F2" => Ok(Code::F2),
-            "F3" => Ok(Code::F3),
-            "F4" => Ok(Code::F4),
-            "F5" => Ok(Code::F5),
-            "F6" => Ok(Code::F6),
-            "F7" => Ok(Code::F7),
-            "F8" => Ok(Code::F8),
-            "F9" => Ok(Code::F9),
-            "F10" => Ok(Code::F10),
-            "F11" => Ok(Code::F11),
-            "F12" => Ok(Code::F12),
-            "SPACE" => Ok(Code::Space),
-            "ENTER" | "RETURN" => Ok(Code::Enter),
-            "TAB" => Ok(Code::Tab),
-            "BACKSPACE" => Ok(Code::Backspace),
-            "ESCAPE" | "ESC" => Ok(Code::Escape),
-            _ => anyhow::bail!("Unknown key code: {}", key),
-        }
-    }
 
-    pub fn poll_event(&self) -> Option<HotkeyEvent> {
-        if let Ok(event) = GlobalHotKeyEvent::receiver().try_recv() {
+            // The other hotkeys act on press; their releases are ignored
+            if event.state != HotKeyState::Pressed {
+                return None;
+            }
             if let Some(toggle) = &self.toggle_hotkey {
                 if event.id == toggle.id() {
                     return Some(HotkeyEvent::ToggleTranscription);
@@ -168,6 +518,19 @@ impl HotkeyManager {
                     return Some(HotkeyEvent::StopTranscription);
                 }
             }
+            if let Some(cancel) = &self.cancel_hotkey {
+                if event.id == cancel.id() {
+                    return Some(HotkeyEvent::CancelTranscription);
+                }
+            }
+            if let Some(pause) = &self.pause_hotkey {
+                if event.id == pause.id() {
+                    return Some(HotkeyEvent::PauseTranscription);
+                }
+            }
+            if let Some(index) = self.profile_hotkeys.iter().position(|hotkey| event.id == hotkey.id()) {
+                return Some(HotkeyEvent::ToggleProfile(index));
+            }
         }
         None
     }
@@ -184,5 +547,110 @@ impl Drop for HotkeyManager {
         if let Some(hotkey) = self.toggle_hotkey {
             let _ = self.manager.unregister(hotkey);
         }
+        if let Some(hotkey) = self.push_to_talk_hotkey {
+            let _ = self.manager.unregister(hotkey);
+        }
+        if let Some(hotkey) = self.cancel_hotkey {
+            let _ = self.manager.unregister(hotkey);
+        }
+        if let Some(hotkey) = self.pause_hotkey {
+            let _ = self.manager.unregister(hotkey);
+        }
+        for hotkey in &self.profile_hotkeys {
+            let _ = self.manager.unregister(*hotkey);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_hotkey_names_and_aliases() {
+        assert_eq!(parse_hotkey("Cmd+Shift+T").unwrap(),
+                   HotKey::new(Some(Modifiers::SUPER | Modifiers::SHIFT), Code::KeyT));
+        assert_eq!(parse_hotkey("option + space").unwrap(),
+                   HotKey::new(Some(Modifiers::ALT), Code::Space));
+        assert_eq!(parse_hotkey("F13").unwrap(), HotKey::new(Some(Modifiers::empty()), Code::F13));
+        assert_eq!(parse_key_code("PgDn").unwrap(), Code::PageDown);
+        assert_eq!(parse_key_code("arrowleft").unwrap(), Code::ArrowLeft);
+        assert_eq!(parse_key_code("/").unwrap(), Code::Slash);
+        assert_eq!(parse_key_code("NumpadPlus").unwrap(), Code::NumpadAdd);
+        assert_eq!(parse_key_code("PlayPause").unwrap(), Code::MediaPlayPause);
+    }
+
+    #[test]
+    fn test_parse_hotkey_errors() {
+        let error = parse_hotkey("Cmd+Escpe").unwrap_err().to_string();
+        assert!(error.contains("did you mean \"Escape\""), "{}", error);
+        let error = parse_hotkey("Shfit+F5").unwrap_err().to_string();
+        assert!(error.contains("did you mean \"Shift\""), "{}", error);
+
+        assert!(parse_key_code("Hyperdrive").unwrap_err().to_string().ends_with("\"Hyperdrive\""));
+        assert!(parse_hotkey("Cmd+Shift").is_err());
+        assert!(parse_hotkey("Cmd+A+B").is_err());
+        assert!(parse_hotkey("Cmd++").is_err());
+        assert!(parse_hotkey("").is_err());
+    }
+
+    #[test]
+    fn test_levenshtein() {
+        assert_eq!(levenshtein("escpe", "escape"), 1);
+        assert_eq!(levenshtein("f31", "f13"), 2);
+        assert_eq!(levenshtein("", "tab"), 3);
+    }
+
+    #[test]
+    fn test_push_to_talk_stops_after_grace_period() {
+        let start = Instant::now();
+        let mut push_to_talk = PushToTalk::new(Duration::from_millis(300));
+
+        assert!(push_to_talk.press(false));
+        assert!(!push_to_talk.poll_stop(start + Duration::from_secs(5))); // Still held
+
+        push_to_talk.release(start);
+        assert!(!push_to_talk.poll_stop(start + Duration::from_millis(299)));
+        assert!(push_to_talk.poll_stop(start + Duration::from_millis(300)));
+        assert!(!push_to_talk.poll_stop(start + Duration::from_millis(400))); // Only once
+    }
+
+    #[test]
+    fn test_push_to_talk_press_within_grace_continues() {
+        let start = Instant::now();
+        let mut push_to_talk = PushToTalk::new(Duration::from_millis(300));
+
+        assert!(push_to_talk.press(false));
+        push_to_talk.release(start);
+        assert!(!push_to_talk.press(false)); // Same recording
+        assert!(!push_to_talk.poll_stop(start + Duration::from_secs(1)));
+
+        push_to_talk.release(start + Duration::from_secs(1));
+        assert!(push_to_talk.poll_stop(start + Duration::from_secs(2)));
+        assert!(push_to_talk.press(false)); // New recording
+    }
+
+    #[test]
+    fn test_push_to_talk_leaves_other_sessions_alone() {
+        let start = Instant::now();
+        let mut push_to_talk = PushToTalk::new(Duration::from_millis(300));
+
+        // A tap during a toggle session doesn't take it over
+        assert!(!push_to_talk.press(true));
+        push_to_talk.release(start);
+        assert!(!push_to_talk.poll_stop(start + Duration::from_secs(1)));
+
+        // Stopping a push-to-talk recording with the stop hotkey disarms it
+        assert!(push_to_talk.press(false));
+        push_to_talk.reset();
+        push_to_talk.release(start);
+        assert!(!push_to_talk.poll_stop(start + Duration::from_secs(1)));
+
+        // Within the grace period, the key still continues its own recording
+        assert!(push_to_talk.press(false));
+        push_to_talk.release(start);
+        assert!(!push_to_talk.press(true));
+        push_to_talk.release(start + Duration::from_secs(1));
+        assert!(push_to_talk.poll_stop(start + Duration::from_secs(2)));
     }
 }
@@ -2,6 +2,7 @@
 // This module simulates the hybrid streaming approach for testing
 
 use crate::transcription::Transcriber;
+use crate::vad::{rms, RmsVad, VoiceActivityDetector};
 
 #[derive(Debug, Clone)]
 pub struct KeyboardAction {
@@ -23,6 +24,17 @@ pub fn simulate_hybrid_vad(
     transcriber: &Transcriber,
     chunk_duration_ms: u64,
     silence_threshold: f32,
+) -> HybridVadResult {
+    let mut detector = RmsVad::new(silence_threshold);
+    simulate_hybrid_vad_with_detector(audio_data, transcriber, chunk_duration_ms, &mut detector)
+}
+
+/// Same as `simulate_hybrid_vad`, with any voice activity detector (e.g. adaptive)
+pub fn simulate_hybrid_vad_with_detector(
+    audio_data: &[f32],
+    transcriber: &Transcriber,
+    chunk_duration_ms: u64,
+    detector: &mut dyn VoiceActivityDetector,
 ) -> HybridVadResult {
     let samples_per_chunk = (16000 * chunk_duration_ms / 1000) as usize;
     let window_duration_ms = 5000; // 5 second sliding window
@@ -67,12 +79,13 @@ pub fn simulate_hybrid_vad(
         chunk_num += 1;
 
         // Check for silence
-        let is_silence = is_silence_chunk(new_audio, silence_threshold);
-        let rms = calculate_rms(new_audio);
+        let is_silence = !detector.is_speech(new_audio);
+        let rms = rms(new_audio);
+        let threshold = detector.threshold().unwrap_or(0.0);
 
         if is_silence {
             silence_streak += 1;
-            println!("Chunk {}: 🔇 Silence (streak: {}, RMS: {:.4})", chunk_num, silence_streak, rms);
+            println!("Chunk {}: 🔇 Silence (streak: {}, RMS: {:.4}, threshold: {:.4})", chunk_num, silence_streak, rms, threshold);
 
             // VAD commit after 3 silent chunks
             if silence_streak >= 3 && !vad_buffer.is_empty() {
@@ -120,9 +133,9 @@ pub fn simulate_hybrid_vad(
 
         // Speech detected
         if silence_streak > 0 {
-            println!("Chunk {}: 🔊 Speech after {} silent chunks (RMS: {:.4})", chunk_num, silence_streak, rms);
+            println!("Chunk {}: 🔊 Speech after {} silent chunks (RMS: {:.4}, threshold: {:.4})", chunk_num, silence_streak, rms, threshold);
         } else {
-            println!("Chunk {}: 🔊 Speech (RMS: {:.4})", chunk_num, rms);
+            println!("Chunk {}: 🔊 Speech (RMS: {:.4}, threshold: {:.4})", chunk_num, rms, threshold);
         }
         silence_streak = 0;
         chunks_since_vad_commit += 1;
@@ -217,14 +230,3 @@ fn replay_keyboard_actions(actions: &[KeyboardAction]) -> String {
     screen
 }
 
-fn calculate_rms(audio: &[f32]) -> f32 {
-    if audio.is_empty() {
-        return 0.0;
-    }
-    let sum_squares: f32 = audio.iter().map(|&x| x * x).sum();
-    (sum_squares / audio.len() as f32).sqrt()
-}
-
-fn is_silence_chunk(audio: &[f32], threshold: f32) -> bool {
-    calculate_rms(audio) < threshold
-}
@@ -1,15 +1,52 @@
+use std::sync::Mutex;
+use std::time::Duration;
+
+// Global lock to serialize keyboard events (prevent race conditions)
+static KEYBOARD_LOCK: Mutex<()> = Mutex::new(());
+
+/// How long to wait after posting backspaces before typing replacement text
+///
+/// The target application has to process the deletes first, otherwise the new text can
+/// interleave with them.
+fn delete_delay(delete_count: usize) -> Duration {
+    Duration::from_millis((delete_count * 2).clamp(10, 200) as u64)
+}
+
+/// Append text at the cursor of the focused application
+pub fn append_text(text: &str) {
+    #[cfg(target_os = "macos")]
+    macos::append_text(text);
+    #[cfg(target_os = "linux")]
+    linux::append_text(text);
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+    {
+        let _ = text;
+        eprintln!("Keyboard typing not supported on this platform");
+    }
+}
+
+/// Delete `delete_count` characters before the cursor, then type `new_text`
+pub fn replace_text_with_backspace(delete_count: usize, new_text: &str) {
+    #[cfg(target_os = "macos")]
+    macos::replace_text_with_backspace(delete_count, new_text);
+    #[cfg(target_os = "linux")]
+    linux::replace_text_with_backspace(delete_count, new_text);
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+    {
+        let _ = (delete_count, new_text);
+        eprintln!("Keyboard replacement not supported on this platform");
+    }
+}
+
 #[cfg(target_os = "macos")]
 pub mod macos {
     use core_graphics::event::{CGEvent, CGEventTapLocation, CGKeyCode};
     use core_graphics::event_source::{CGEventSource, CGEventSourceStateID};
-    use std::sync::Mutex;
     use std::sync::atomic::{AtomicBool, Ordering};
+    use super::{delete_delay, KEYBOARD_LOCK};
 
     const KEY_DELETE: CGKeyCode = 51; // Backspace key code
 
-    // Global lock to serialize keyboard events (prevent race conditions)
-    static KEYBOARD_LOCK: Mutex<()> = Mutex::new(());
-
     // Track if we've checked accessibility permissions
     static ACCESSIBILITY_CHECKED: AtomicBool = AtomicBool::new(false);
     static ACCESSIBILITY_GRANTED: AtomicBool = AtomicBool::new(false);
@@ -118,8 +155,7 @@ pub mod macos {
 
         // Delay to ensure deletes are processed
         if delete_count > 0 {
-            let delay_ms = (delete_count * 2).max(10).min(200);
-            std::thread::sleep(std::time::Duration::from_millis(delay_ms as u64));
+            std::thread::sleep(delete_delay(delete_count));
         }
 
         // Type the new text
@@ -137,13 +173,594 @@ pub mod macos {
 
 }
 
-#[cfg(not(target_os = "macos"))]
-pub mod macos {
-    pub fn append_text(_text: &str) {
-        eprintln!("Keyboard typing only supported on macOS");
+#[cfg(target_os = "linux")]
+pub mod linux {
+    use anyhow::Result;
+    use std::sync::Mutex;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use super::{delete_delay, KEYBOARD_LOCK};
+
+    /// Keyboard injection backend, chosen on first use
+    enum Backend {
+        /// Virtual keyboard created through /dev/uinput (works on X11, Wayland and the console)
+        Uinput(uinput::VirtualKeyboard),
+        /// XTest extension (X11 only, no special permissions needed)
+        XTest(Box<xtest::XTestKeyboard>),
+    }
+
+    impl Backend {
+        fn type_text(&mut self, text: &str) -> Result<()> {
+            match self {
+                Backend::Uinput(keyboard) => keyboard.type_text(text),
+                Backend::XTest(keyboard) => keyboard.type_text(text),
+            }
+        }
+
+        fn backspace(&mut self, count: usize) -> Result<()> {
+            match self {
+                Backend::Uinput(keyboard) => keyboard.backspace(count),
+                Backend::XTest(keyboard) => keyboard.backspace(count),
+            }
+        }
+    }
+
+    static BACKEND: Mutex<Option<Backend>> = Mutex::new(None);
+
+    // Track if we've tried to open a backend (only warn once)
+    static BACKEND_CHECKED: AtomicBool = AtomicBool::new(false);
+
+    /// Open a keyboard backend
+    ///
+    /// XTest types keysyms, so it follows the keyboard layout; it's preferred in an X11
+    /// session. uinput sends key codes for a US layout and is used on Wayland and the
+    /// console, where XTest can't reach the focused application.
+    fn open_backend() -> Option<Backend> {
+        let has_display = std::env::var_os("DISPLAY").is_some();
+        let x11_session = has_display && std::env::var_os("WAYLAND_DISPLAY").is_none();
+
+        if x11_session {
+            if let Some(backend) = open_xtest() {
+                return Some(backend);
+            }
+        }
+
+        match uinput::VirtualKeyboard::new() {
+            Ok(keyboard) => {
+                println!("⌨️  Keyboard injection via /dev/uinput (US keyboard layout)");
+                return Some(Backend::Uinput(keyboard));
+            }
+            Err(e) => eprintln!("⚠️  uinput keyboard unavailable: {:#}", e),
+        }
+
+        // XWayland: only reaches X11 applications, but better than nothing
+        if has_display && !x11_session {
+            if let Some(backend) = open_xtest() {
+                return Some(backend);
+            }
+        }
+
+        eprintln!("\n⚠️  WARNING: No keyboard injection backend available!");
+        eprintln!("    Keyboard typing will not work.");
+        eprintln!("    Either allow access to /dev/uinput:");
+        eprintln!("    1. Add a udev rule: KERNEL==\"uinput\", GROUP=\"input\", MODE=\"0660\"");
+        eprintln!("    2. Add yourself to the input group: sudo usermod -aG input $USER");
+        eprintln!("    3. Log out and back in");
+        eprintln!("    Or run under an X11 session with libXtst installed.\n");
+
+        None
+    }
+
+    fn open_xtest() -> Option<Backend> {
+        match xtest::XTestKeyboard::new() {
+            Ok(keyboard) => {
+                println!("⌨️  Keyboard injection via X11 XTest");
+                Some(Backend::XTest(Box::new(keyboard)))
+            }
+            Err(e) => {
+                eprintln!("⚠️  XTest keyboard unavailable: {:#}", e);
+                None
+            }
+        }
+    }
+
+    /// Run `f` with the keyboard backend, opening it on first use
+    ///
+    /// Returns false if no backend is available. Callers must hold `KEYBOARD_LOCK`.
+    fn with_backend(f: impl FnOnce(&mut Backend) -> Result<()>) -> bool {
+        let mut backend = BACKEND.lock().unwrap();
+
+        if backend.is_none() && !BACKEND_CHECKED.swap(true, Ordering::Relaxed) {
+            *backend = open_backend();
+        }
+
+        match backend.as_mut() {
+            Some(backend) => {
+                if let Err(e) = f(backend) {
+                    eprintln!("❌ Failed to post keyboard events: {:#}", e);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Append text (terminal-friendly - just adds new characters)
+    pub fn append_text(text: &str) {
+        if text.is_empty() {
+            return;
+        }
+
+        let _lock = KEYBOARD_LOCK.lock().unwrap();
+
+        println!("⌨️  Typing {} chars: {:?}", text.len(), text);
+        if !with_backend(|backend| backend.type_text(text)) {
+            eprintln!("⚠️  Skipping text append - no keyboard backend");
+        }
+    }
+
+    /// Replace text by deleting old chars and typing new
+    pub fn replace_text_with_backspace(delete_count: usize, new_text: &str) {
+        if delete_count == 0 && new_text.is_empty() {
+            return;
+        }
+
+        let _lock = KEYBOARD_LOCK.lock().unwrap();
+
+        println!("⌨️  Replacing text: deleting {} chars, typing {} chars: {:?}", delete_count, new_text.len(), new_text);
+
+        let available = with_backend(|backend| {
+            backend.backspace(delete_count)?;
+
+            // Delay to ensure deletes are processed
+            if delete_count > 0 {
+                std::thread::sleep(delete_delay(delete_count));
+            }
+
+            backend.type_text(new_text)
+        });
+
+        if !available {
+            eprintln!("⚠️  Skipping text replacement - no keyboard backend");
+        }
+    }
+
+    /// Virtual keyboard through the kernel's uinput interface
+    mod uinput {
+        use anyhow::{bail, Result};
+        use std::ffi::CString;
+        use std::mem::size_of;
+        use std::time::Duration;
+
+        // ioctl request codes from <linux/uinput.h>
+        const UI_SET_EVBIT: libc::c_ulong = 0x4004_5564;
+        const UI_SET_KEYBIT: libc::c_ulong = 0x4004_5565;
+        const UI_DEV_SETUP: libc::c_ulong = 0x405c_5503;
+        const UI_DEV_CREATE: libc::c_ulong = 0x5501;
+        const UI_DEV_DESTROY: libc::c_ulong = 0x5502;
+
+        const EV_SYN: u16 = 0x00;
+        const EV_KEY: u16 = 0x01;
+        const SYN_REPORT: u16 = 0;
+        const BUS_VIRTUAL: u16 = 0x06;
+
+        // Key codes from <linux/input-event-codes.h>
+        const KEY_ESC: u16 = 1;
+        const KEY_MINUS: u16 = 12;
+        const KEY_EQUAL: u16 = 13;
+        const KEY_BACKSPACE: u16 = 14;
+        const KEY_TAB: u16 = 15;
+        const KEY_U: u16 = 22;
+        const KEY_LEFTBRACE: u16 = 26;
+        const KEY_RIGHTBRACE: u16 = 27;
+        const KEY_ENTER: u16 = 28;
+        const KEY_LEFTCTRL: u16 = 29;
+        const KEY_SEMICOLON: u16 = 39;
+        const KEY_APOSTROPHE: u16 = 40;
+        const KEY_GRAVE: u16 = 41;
+        const KEY_LEFTSHIFT: u16 = 42;
+        const KEY_BACKSLASH: u16 = 43;
+        const KEY_COMMA: u16 = 51;
+        const KEY_DOT: u16 = 52;
+        const KEY_SLASH: u16 = 53;
+        const KEY_SPACE: u16 = 57;
+
+        #[repr(C)]
+        struct InputId {
+            bustype: u16,
+            vendor: u16,
+            product: u16,
+            version: u16,
+        }
+
+        #[repr(C)]
+        struct UinputSetup {
+            id: InputId,
+            name: [u8; 80],
+            ff_effects_max: u32,
+        }
+
+        #[repr(C)]
+        struct InputEvent {
+            time: libc::timeval,
+            type_: u16,
+            code: u16,
+            value: i32,
+        }
+
+        /// Map an ASCII character to a key code on a US layout, plus whether Shift is needed
+        pub(super) fn ascii_key(c: char) -> Option<(u16, bool)> {
+            const ROWS: [(&str, u16); 3] = [("qwertyuiop", 16), ("asdfghjkl", 30), ("zxcvbnm", 44)];
+
+            if c.is_ascii_alphabetic() {
+                let lower = c.to_ascii_lowercase();
+                for (row, first_code) in ROWS {
+                    if let Some(pos) = row.find(lower) {
+                        return Some((first_code + pos as u16, c.is_ascii_uppercase()));
+                    }
+                }
+            }
+
+            let key = match c {
+                '1'..='9' => (c as u16 - '1' as u16 + 2, false),
+                '0' => (11, false),
+                '!' => (2, true),
+                '@' => (3, true),
+                '#' => (4, true),
+                '$' => (5, true),
+                '%' => (6, true),
+                '^' => (7, true),
+                '&' => (8, true),
+                '*' => (9, true),
+                '(' => (10, true),
+                ')' => (11, true),
+                '-' => (KEY_MINUS, false),
+                '_' => (KEY_MINUS, true),
+                '=' => (KEY_EQUAL, false),
+                '+' => (KEY_EQUAL, true),
+                '[' => (KEY_LEFTBRACE, false),
+                '{' => (KEY_LEFTBRACE, true),
+                ']' => (KEY_RIGHTBRACE, false),
+                '}' => (KEY_RIGHTBRACE, true),
+                ';' => (KEY_SEMICOLON, false),
+                ':' => (KEY_SEMICOLON, true),
+                '\'' => (KEY_APOSTROPHE, false),
+                '"' => (KEY_APOSTROPHE, true),
+                '`' => (KEY_GRAVE, false),
+                '~' => (KEY_GRAVE, true),
+                '\\' => (KEY_BACKSLASH, false),
+                '|' => (KEY_BACKSLASH, true),
+                ',' => (KEY_COMMA, false),
+                '<' => (KEY_COMMA, true),
+                '.' => (KEY_DOT, false),
+                '>' => (KEY_DOT, true),
+                '/' => (KEY_SLASH, false),
+                '?' => (KEY_SLASH, true),
+                ' ' => (KEY_SPACE, false),
+                '\t' => (KEY_TAB, false),
+                '\n' => (KEY_ENTER, false),
+                _ => return None,
+            };
+            Some(key)
+        }
+
+        pub struct VirtualKeyboard {
+            fd: libc::c_int,
+        }
+
+        impl VirtualKeyboard {
+            pub fn new() -> Result<Self> {
+                let path = CString::new("/dev/uinput").expect("static path has no NUL bytes");
+                let fd = unsafe { libc::open(path.as_ptr(), libc::O_WRONLY | libc::O_NONBLOCK) };
+                if fd < 0 {
+                    bail!("Failed to open /dev/uinput: {}", std::io::Error::last_os_error());
+                }
+
+                // From here on, Drop closes the fd if setup fails
+                let keyboard = VirtualKeyboard { fd };
+
+                keyboard.ioctl(UI_SET_EVBIT, EV_KEY as libc::c_int)?;
+                for code in KEY_ESC..=KEY_SPACE {
+                    keyboard.ioctl(UI_SET_KEYBIT, code as libc::c_int)?;
+                }
+
+                let mut setup = UinputSetup {
+                    id: InputId {
+                        bustype: BUS_VIRTUAL,
+                        vendor: 0x1209,
+                        product: 0x7470,
+                        version: 1,
+                    },
+                    name: [0; 80],
+                    ff_effects_max: 0,
+                };
+                let name = b"live-transcribe virtual keyboard";
+                setup.name[..name.len()].copy_from_slice(name);
+
+                if unsafe { libc::ioctl(fd, UI_DEV_SETUP as _, &setup) } < 0 {
+                    bail!("UI_DEV_SETUP failed: {}", std::io::Error::last_os_error());
+                }
+                if unsafe { libc::ioctl(fd, UI_DEV_CREATE as _) } < 0 {
+                    bail!("UI_DEV_CREATE failed: {}", std::io::Error::last_os_error());
+                }
+
+                // Give udev and the display server time to pick up the new device,
+                // otherwise the first keystrokes are lost
+                std::thread::sleep(Duration::from_millis(200));
+
+                Ok(keyboard)
+            }
+
+            fn ioctl(&self, request: libc::c_ulong, value: libc::c_int) -> Result<()> {
+                if unsafe { libc::ioctl(self.fd, request as _, value) } < 0 {
+                    bail!("uinput ioctl {:#x} failed: {}", request, std::io::Error::last_os_error());
+                }
+                Ok(())
+            }
+
+            fn emit(&self, type_: u16, code: u16, value: i32) -> Result<()> {
+                let event = InputEvent {
+                    time: libc::timeval { tv_sec: 0, tv_usec: 0 },
+                    type_,
+                    code,
+                    value,
+                };
+                let written = unsafe {
+                    libc::write(
+                        self.fd,
+                        &event as *const InputEvent as *const libc::c_void,
+                        size_of::<InputEvent>(),
+                    )
+                };
+                if written != size_of::<InputEvent>() as isize {
+                    bail!("Failed to write uinput event: {}", std::io::Error::last_os_error());
+                }
+                Ok(())
+            }
+
+            fn key(&self, code: u16, pressed: bool) -> Result<()> {
+                self.emit(EV_KEY, code, pressed as i32)?;
+                self.emit(EV_SYN, SYN_REPORT, 0)
+            }
+
+            fn tap(&self, code: u16, shift: bool) -> Result<()> {
+                if shift {
+                    self.key(KEY_LEFTSHIFT, true)?;
+                }
+                self.key(code, true)?;
+                self.key(code, false)?;
+                if shift {
+                    self.key(KEY_LEFTSHIFT, false)?;
+                }
+                Ok(())
+            }
+
+            /// Enter a character through the Ctrl+Shift+U hex input method (GTK/IBus)
+            fn type_unicode(&self, c: char) -> Result<()> {
+                self.key(KEY_LEFTCTRL, true)?;
+                self.key(KEY_LEFTSHIFT, true)?;
+                self.tap(KEY_U, false)?;
+                self.key(KEY_LEFTSHIFT, false)?;
+                self.key(KEY_LEFTCTRL, false)?;
+
+                for digit in format!("{:x}", c as u32).chars() {
+                    if let Some((code, shift)) = ascii_key(digit) {
+                        self.tap(code, shift)?;
+                    }
+                }
+
+                self.tap(KEY_SPACE, false)
+            }
+
+            pub fn type_text(&self, text: &str) -> Result<()> {
+                for c in text.chars() {
+                    match ascii_key(c) {
+                        Some((code, shift)) => self.tap(code, shift)?,
+                        None => self.type_unicode(c)?,
+                    }
+                }
+                Ok(())
+            }
+
+            pub fn backspace(&self, count: usize) -> Result<()> {
+                for _ in 0..count {
+                    self.tap(KEY_BACKSPACE, false)?;
+                }
+                Ok(())
+            }
+        }
+
+        impl Drop for VirtualKeyboard {
+            fn drop(&mut self) {
+                unsafe {
+                    libc::ioctl(self.fd, UI_DEV_DESTROY as _);
+                    libc::close(self.fd);
+                }
+            }
+        }
+    }
+
+    /// Keyboard injection through the X11 XTest extension
+    ///
+    /// Characters are typed by temporarily binding their keysym to an unused keycode,
+    /// which works for any Unicode character regardless of the active keyboard layout.
+    mod xtest {
+        use anyhow::{bail, Context, Result};
+        use std::os::raw::{c_int, c_uint, c_ulong};
+        use std::ptr;
+        use std::time::Duration;
+        use x11_dl::keysym::{XK_BackSpace, XK_Return, XK_Tab};
+        use x11_dl::xlib::{self, Xlib};
+        // x11-dl exposes the XTest bindings under the name Xf86vmode
+        use x11_dl::xtest::Xf86vmode as XTest;
+
+        pub struct XTestKeyboard {
+            xlib: Xlib,
+            xtest: XTest,
+            display: *mut xlib::Display,
+            spare_keycode: c_int,
+        }
+
+        // The display connection is only used while holding KEYBOARD_LOCK
+        unsafe impl Send for XTestKeyboard {}
+
+        impl XTestKeyboard {
+            pub fn new() -> Result<Self> {
+                let xlib = Xlib::open().context("Failed to load libX11")?;
+                let xtest = XTest::open().context("Failed to load libXtst")?;
+
+                let display = unsafe { (xlib.XOpenDisplay)(ptr::null()) };
+                if display.is_null() {
+                    bail!("Failed to open X display");
+                }
+
+                let mut keyboard = XTestKeyboard {
+                    xlib,
+                    xtest,
+                    display,
+                    spare_keycode: 0,
+                };
+
+                let (mut event_base, mut error_base, mut major, mut minor) = (0, 0, 0, 0);
+                let has_xtest = unsafe {
+                    (keyboard.xtest.XTestQueryExtension)(
+                        display,
+                        &mut event_base,
+                        &mut error_base,
+                        &mut major,
+                        &mut minor,
+                    )
+                };
+                if has_xtest == 0 {
+                    bail!("X server does not support the XTEST extension");
+                }
+
+                keyboard.spare_keycode = keyboard
+                    .find_spare_keycode()
+                    .context("No unused keycode available for typing")?;
+
+                Ok(keyboard)
+            }
+
+            /// Find a keycode that has no keysyms bound to it
+            fn find_spare_keycode(&self) -> Option<c_int> {
+                let (mut min_keycode, mut max_keycode) = (0, 0);
+                let mut keysyms_per_keycode = 0;
+
+                unsafe {
+                    (self.xlib.XDisplayKeycodes)(self.display, &mut min_keycode, &mut max_keycode);
+                    let count = max_keycode - min_keycode + 1;
+                    let mapping = (self.xlib.XGetKeyboardMapping)(
+                        self.display,
+                        min_keycode as u8,
+                        count,
+                        &mut keysyms_per_keycode,
+                    );
+                    if mapping.is_null() {
+                        return None;
+                    }
+
+                    let per_keycode = keysyms_per_keycode as usize;
+                    let keysyms = std::slice::from_raw_parts(mapping, count as usize * per_keycode);
+                    let spare = (0..count as usize)
+                        .rev()
+                        .find(|&i| keysyms[i * per_keycode..(i + 1) * per_keycode].iter().all(|&k| k == 0))
+                        .map(|i| min_keycode + i as c_int);
+
+                    (self.xlib.XFree)(mapping as *mut _);
+                    spare
+                }
+            }
+
+            fn tap(&self, keycode: c_uint) {
+                unsafe {
+                    (self.xtest.XTestFakeKeyEvent)(self.display, keycode, 1, 0);
+                    (self.xtest.XTestFakeKeyEvent)(self.display, keycode, 0, 0);
+                    (self.xlib.XSync)(self.display, 0);
+                }
+            }
+
+            fn tap_keysym(&self, keysym: c_uint) {
+                let keycode = unsafe { (self.xlib.XKeysymToKeycode)(self.display, keysym as c_ulong) };
+                if keycode != 0 {
+                    self.tap(keycode as c_uint);
+                }
+            }
+
+            /// Bind `keysym` to the spare keycode (0 unbinds it)
+            fn bind_spare(&self, keysym: c_ulong) {
+                // Bind both columns so letters are not case-converted by Xlib
+                let mut keysyms = [keysym, keysym];
+                unsafe {
+                    (self.xlib.XChangeKeyboardMapping)(self.display, self.spare_keycode, 2, keysyms.as_mut_ptr(), 1);
+                    (self.xlib.XSync)(self.display, 0);
+                }
+            }
+
+            fn char_keysym(c: char) -> c_ulong {
+                let code = c as u32;
+                if (0x20..=0x7e).contains(&code) || (0xa0..=0xff).contains(&code) {
+                    code as c_ulong
+                } else {
+                    (0x0100_0000 | code) as c_ulong
+                }
+            }
+
+            pub fn type_text(&self, text: &str) -> Result<()> {
+                for c in text.chars() {
+                    match c {
+                        '\n' => self.tap_keysym(XK_Return),
+                        '\t' => self.tap_keysym(XK_Tab),
+                        _ => {
+                            self.bind_spare(Self::char_keysym(c));
+                            self.tap(self.spare_keycode as c_uint);
+                            // Let clients process the key before the mapping changes again
+                            std::thread::sleep(Duration::from_millis(2));
+                        }
+                    }
+                }
+                self.bind_spare(0);
+                Ok(())
+            }
+
+            pub fn backspace(&self, count: usize) -> Result<()> {
+                for _ in 0..count {
+                    self.tap_keysym(XK_BackSpace);
+                }
+                Ok(())
+            }
+        }
+
+        impl Drop for XTestKeyboard {
+            fn drop(&mut self) {
+                unsafe {
+                    (self.xlib.XCloseDisplay)(self.display);
+                }
+            }
+        }
     }
 
-    pub fn replace_text_with_backspace(_delete_count: usize, _new_text: &str) {
-        eprintln!("Keyboard replacement only supported on macOS");
+    #[cfg(test)]
+    mod tests {
+        use super::uinput::ascii_key;
+
+        #[test]
+        fn test_ascii_key_letters_and_shift() {
+            assert_eq!(ascii_key('q'), Some((16, false)));
+            assert_eq!(ascii_key('A'), Some((30, true)));
+            assert_eq!(ascii_key('m'), Some((50, false)));
+        }
+
+        #[test]
+        fn test_ascii_key_digits_and_punctuation() {
+            assert_eq!(ascii_key('1'), Some((2, false)));
+            assert_eq!(ascii_key('0'), Some((11, false)));
+            assert_eq!(ascii_key('?'), Some((53, true)));
+            assert_eq!(ascii_key(' '), Some((57, false)));
+        }
+
+        #[test]
+        fn test_ascii_key_non_ascii() {
+            assert_eq!(ascii_key('é'), None);
+        }
     }
 }
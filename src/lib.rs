@@ -1,10 +1,20 @@
 // Library exports for testing
 pub mod audio;
+pub mod clipboard;
+pub mod commit_policy;
 pub mod config;
 pub mod constants;
+pub mod control;
+pub mod hotkey;
+pub mod keyboard;
+pub mod output;
+pub mod resampler;
+pub mod ring_buffer;
+pub mod sample_convert;
 pub mod sandbox;
 pub mod text_diff;
 pub mod transcription;
 pub mod transcription_state;
 pub mod transcription_worker;
+pub mod vad;
 pub mod hybrid_vad;
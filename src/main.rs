@@ -1,26 +1,36 @@
 mod audio;
+mod clipboard;
+mod commit_policy;
 mod config;
 mod constants;
+mod control;
 mod hotkey;
 mod keyboard;
 mod model_download;
+mod output;
+mod resampler;
+mod ring_buffer;
+mod sample_convert;
 mod sandbox;
 mod text_diff;
 mod transcription;
 mod transcription_state;
 mod transcription_worker;
 mod tray;
+mod vad;
 pub mod hybrid_vad;
 
 use anyhow::Result;
 use audio::AudioCapture;
 use clap::{Parser, Subcommand};
-use config::{Config, TranscriptionConfig};
-use hotkey::{HotkeyEvent, HotkeyManager};
+use config::{Config, OutputMode, TranscriptionConfig};
+use control::ControlCommand;
+use hotkey::{HotkeyEvent, HotkeyManager, PushToTalk};
 use model_download::ModelDownloader;
+use output::OutputSink;
 use transcription::{Transcriber, TranscriberWithState};
 use transcription_state::{Action, TranscriptionState};
-use transcription_worker::TranscriptionWorker;
+use transcription_worker::{PendingModel, TranscriptionWorker};
 use tray::{TrayApp, TrayMenuEvent};
 use tao::event_loop::{EventLoop, ControlFlow};
 #[cfg(target_os = "macos")]
@@ -56,6 +66,13 @@ enum Commands {
         /// Name of the test recording to replay
         name: String,
     },
+    /// List available audio input devices
+    ListDevices,
+    /// Switch the running app to another model without restarting it
+    SwitchModel {
+        /// Model to switch to (e.g., small.en, medium). It must have been downloaded
+        model: String,
+    },
 }
 
 fn main() -> Result<()> {
@@ -75,6 +92,12 @@ fn main() -> Result<()> {
             // Don't enable sandbox for test commands
             return test_replay_command(&name);
         }
+        Some(Commands::ListDevices) => {
+            return list_devices_command();
+        }
+        Some(Commands::SwitchModel { model }) => {
+            return switch_model_command(&model);
+        }
         None => {
             // Initialize sandbox for main app ONLY
             if let Err(e) = sandbox::macos::init() {
@@ -295,6 +318,49 @@ fn simulate_streaming_transcription(audio_data: &[f32], transcriber: &Transcribe
     println!();
 }
 
+fn list_devices_command() -> Result<()> {
+    let (devices, default_device) = audio::list_input_devices()?;
+    let config = Config::load_or_create()?;
+
+    if devices.is_empty() {
+        println!("No audio input devices found");
+        return Ok(());
+    }
+
+    let selected = config
+        .audio
+        .device
+        .as_deref()
+        .and_then(|requested| audio::match_device_name(&devices, requested));
+
+    println!("Audio input devices:");
+    for (i, name) in devices.iter().enumerate() {
+        let mut markers = Vec::new();
+        if default_device.as_deref() == Some(name.as_str()) {
+            markers.push("default");
+        }
+        if selected == Some(i) {
+            markers.push("selected");
+        }
+
+        if markers.is_empty() {
+            println!("  {}", name);
+        } else {
+            println!("  {} ({})", name, markers.join(", "));
+        }
+    }
+
+    if let (Some(requested), None) = (&config.audio.device, selected) {
+        println!();
+        println!("⚠️  Configured device \"{}\" is not connected; the default device will be used", requested);
+    }
+
+    println!();
+    println!("Set `audio.device` in ~/.live-transcribe/settings.yaml to a name (or part of one) above.");
+
+    Ok(())
+}
+
 fn test_replay_command(name: &str) -> Result<()> {
     use std::io::Read;
 
@@ -423,9 +489,11 @@ fn test_record_command(name: &str, _duration: u64) -> Result<()> {
     io::stdout().flush()?;
 
     lines.next(); // Wait for Enter
+    drop(lines); // Release the stdin lock for the stop prompt below
 
     // Create audio capture
-    let mut audio_capture = AudioCapture::new()?;
+    let config = Config::load_or_create()?;
+    let mut audio_capture = AudioCapture::with_device(config.audio.device.as_deref())?;
 
     println!();
     println!("🔴 RECORDING - Speak now!");
@@ -434,8 +502,18 @@ fn test_record_command(name: &str, _duration: u64) -> Result<()> {
 
     audio_capture.start_recording()?;
 
-    // Wait for user to press Enter to stop
-    lines.next();
+    // Wait for user to press Enter to stop, collecting audio meanwhile
+    let (enter_tx, enter_rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let mut line = String::new();
+        let _ = io::stdin().read_line(&mut line);
+        let _ = enter_tx.send(());
+    });
+    while let Err(std::sync::mpsc::RecvTimeoutError::Timeout) =
+        enter_rx.recv_timeout(std::time::Duration::from_millis(50))
+    {
+        audio_capture.collect_input();
+    }
 
     let audio_data = audio_capture.stop_recording()?;
 
@@ -535,11 +613,29 @@ fn download_model_command(model_name: &Option<String>) -> Result<()> {
     Ok(())
 }
 
+fn switch_model_command(model_name: &str) -> Result<()> {
+    let models_dir = Config::config_dir()?.join("models");
+    if !models_dir.join(format!("ggml-{}.bin", model_name)).exists() {
+        anyhow::bail!(
+            "Model not found: {}\nDownload it with: live-transcribe download-model {}",
+            model_name,
+            model_name
+        );
+    }
+
+    control::send(&control::inbox_dir()?, &ControlCommand::SwitchModel(model_name.to_string()))?;
+    println!("✓ Asked the running app to switch to {}", model_name);
+    println!("  The model loads in the background; the switch happens once no recording is in progress.");
+    println!("  To keep using it after a restart, update ~/.live-transcribe/settings.yaml.");
+
+    Ok(())
+}
+
 fn run_app() -> Result<()> {
     println!("Live Transcribe - System Tray Application");
 
     // Load configuration
-    let config = Config::load_or_create()?;
+    let mut config = Config::load_or_create()?;
     println!("Configuration loaded successfully");
 
     // Check if models exist, show helpful message if not
@@ -558,6 +654,16 @@ fn run_app() -> Result<()> {
         anyhow::bail!("VAD model not found");
     }
 
+    // Profile models are loaded when a profile's session starts, so only warn about them
+    for profile in &config.profiles {
+        if let Some(model) = &profile.model {
+            if !models_dir.join(format!("ggml-{}.bin", model)).exists() {
+                eprintln!("⚠️  Model {} for profile \"{}\" not found", model, profile.name);
+                eprintln!("   Download it with: cargo run -- download-model {}", model);
+            }
+        }
+    }
+
     // Load model once and share between VAD and live preview workers
     // This saves 300-600MB of memory compared to loading twice
     println!("Initializing transcriber:");
@@ -566,13 +672,17 @@ fn run_app() -> Result<()> {
     let shared_transcriber = TranscriberWithState::new(config.transcription.clone())?;
 
     // Initialize transcription worker threads with shared model
-    let (transcription_worker, transcription_results) =
+    let (mut transcription_worker, transcription_results) =
         TranscriptionWorker::new(shared_transcriber)?;
 
     println!("Transcription workers initialized (sharing model context)");
 
     // Create audio capture
-    let audio_capture = Arc::new(Mutex::new(AudioCapture::new()?));
+    let mut capture = AudioCapture::with_device(config.audio.device.as_deref())?;
+    if config.audio.always_on {
+        capture.start_monitoring(config.audio.pre_roll_ms)?;
+    }
+    let audio_capture = Arc::new(Mutex::new(capture));
 
     // Create event loop
     let mut event_loop = EventLoop::new();
@@ -582,23 +692,51 @@ fn run_app() -> Result<()> {
     event_loop.set_activation_policy(ActivationPolicy::Accessory);
 
     // Create tray app
-    let mut tray_app = TrayApp::new()?;
+    let installed_models = ModelDownloader::new(models_dir.clone()).installed_models();
+    let mut tray_app = TrayApp::new(&installed_models, &config.transcription.model)?;
     println!("System tray initialized");
 
     // Create hotkey manager
-    let hotkey_manager = HotkeyManager::new(&config.hotkeys)?;
+    let hotkey_manager = HotkeyManager::new(&config.hotkeys, &config.profiles)?;
+    let mut push_to_talk = PushToTalk::new(std::time::Duration::from_millis(config.hotkeys.push_to_talk_grace_ms));
 
     let streaming_mode = config.transcription.streaming;
     let chunk_duration = config.transcription.chunk_duration_ms;
-    let silence_threshold = config.transcription.silence_threshold;
+
+    // Create output sink for transcribed text
+    let mut output_sink = output::build_sink(&config.output)?;
+
+    // Profile and output mode of the current (or last) session
+    let mut session_settings = SessionSettings {
+        profile: None,
+        output_mode: config.output.mode,
+    };
+
+    // Recording session phase (finalizing waits for the last transcriptions after stop)
+    let mut phase = SessionPhase::Idle;
 
     // Create transcription state machine
-    let mut transcription_state = TranscriptionState::new(silence_threshold);
+    let mut transcription_state = TranscriptionState::from_config(&config.transcription);
 
     // Blink timer for recording indicator (blink every 500ms)
     let mut last_blink = std::time::Instant::now();
     let blink_interval = std::time::Duration::from_millis(500);
 
+    // Commands from the command line (`live-transcribe switch-model`); ones left over from
+    // before startup are dropped
+    let control_inbox = control::inbox_dir()?;
+    for command in control::receive(&control_inbox) {
+        println!("Ignoring command sent before startup: {:?}", command);
+    }
+    let mut last_control_poll = std::time::Instant::now();
+    let control_poll_interval = std::time::Duration::from_millis(500);
+
+    // Model loading in the background, applied between sessions
+    let mut pending_model: Option<PendingModel> = None;
+
+    // Profile models loading in the background, and the session waiting for one
+    let mut profile_loads = ProfileLoads::default();
+
     // Main event loop
     event_loop.run(move |_event, _, control_flow| {
         // Use WaitUntil with a short timeout for responsive polling
@@ -607,46 +745,120 @@ fn run_app() -> Result<()> {
         );
 
         // Blink recording indicator if recording
-        if streaming_mode {
-            let capture_guard = audio_capture.lock().unwrap();
-            let is_recording = capture_guard.is_recording();
-            drop(capture_guard);
-
-            if is_recording && last_blink.elapsed() >= blink_interval {
-                tray_app.blink_recording_indicator();
-                last_blink = std::time::Instant::now();
-            }
+        let is_recording = audio_capture.lock().unwrap().is_recording();
+        if is_recording && last_blink.elapsed() >= blink_interval {
+            tray_app.blink_recording_indicator();
+            last_blink = std::time::Instant::now();
         }
 
         // Poll transcription results (non-blocking)
         while let Ok(result) = transcription_results.try_recv() {
             use transcription_worker::TranscriptionResult;
 
-            // Process result through state machine and get keyboard action
-            let action = match result {
+            // Process result through state machine and get keyboard actions
+            let actions = match result {
                 TranscriptionResult::VadCommit { text, request_id } => {
-                    transcription_state.process_vad_result(text, request_id)
+                    vec![transcription_state.process_vad_result(text, request_id)]
                 }
                 TranscriptionResult::LivePreview { text, request_id } => {
-                    transcription_state.process_live_result(text, request_id)
+                    vec![transcription_state.process_live_result(text, request_id)]
                 }
                 TranscriptionResult::Error { error, request_id } => {
                     eprintln!("❌ Transcription error (request {}): {}", request_id, error);
-                    transcription_state.process_error(request_id);
-                    Action::NoAction
+                    transcription_state.process_error(request_id)
+                }
+                TranscriptionResult::Cancelled { request_id } => {
+                    vec![transcription_state.process_cancelled(request_id)]
                 }
             };
 
-            // Execute keyboard action
-            match action {
-                Action::AppendText(text) => {
-                    keyboard::macos::append_text(&text);
+            for action in actions {
+                if let Action::UtteranceDropped { sequence, duration_secs } = action {
+                    eprintln!("⚠️  Sentence {} ({:.1}s) could not be transcribed and was dropped", sequence, duration_secs);
+                    tray_app.show_warning(&format!("A {:.0}s sentence could not be transcribed", duration_secs));
+                    continue;
+                }
+
+                // Send keyboard action to the configured output sink
+                if session_settings.output_mode.types() {
+                    if let Err(e) = output_sink.apply(&action) {
+                        eprintln!("✗ Failed to output text: {}", e);
+                    }
+                }
+            }
+        }
+
+        // Resubmit failed utterances once their backoff has elapsed
+        for action in transcription_state.due_retries(std::time::Instant::now()) {
+            submit_to_worker(&transcription_worker, action);
+        }
+
+        // Once the last transcriptions are back, copy the session's text and go idle
+        if let SessionPhase::Finalizing { since, stall_reported } = phase {
+            let finished = transcription_state.is_finished();
+            let stall_warning = std::time::Duration::from_millis(constants::session::FINALIZE_STALL_WARNING_MS);
+            if !finished && !stall_reported && since.elapsed() >= stall_warning {
+                eprintln!("⚠️  Still waiting for the final transcription after {}s (the cancel hotkey discards the session)", stall_warning.as_secs());
+                tray_app.show_warning("Still waiting for the final transcription");
+                phase = SessionPhase::Finalizing { since, stall_reported: true };
+            }
+            if finished {
+                if session_settings.output_mode.copies() {
+                    let text = transcription_state.committed_text().trim();
+                    if !text.is_empty() {
+                        if let Err(e) = clipboard::copy_text(text) {
+                            eprintln!("✗ {:#}", e);
+                        }
+                    }
                 }
-                Action::ReplaceText { chars_to_delete, new_text } => {
-                    keyboard::macos::replace_text_with_backspace(chars_to_delete, &new_text);
+                println!("✓ Transcription complete");
+                phase = SessionPhase::Idle;
+            }
+        }
+
+        // Poll commands from the command line
+        if last_control_poll.elapsed() >= control_poll_interval {
+            last_control_poll = std::time::Instant::now();
+            for command in control::receive(&control_inbox) {
+                match command {
+                    ControlCommand::SwitchModel(model) => {
+                        println!("Command: Switching to model {}...", model);
+                        load_model(model, &config, &mut pending_model);
+                    }
+                }
+            }
+        }
+
+        // Switch to a model loaded in the background once no session is running
+        if let SessionPhase::Idle = phase {
+            if let Some(loaded) = pending_model.as_ref().and_then(|pending| pending.poll()) {
+                let pending = pending_model.take().unwrap();
+                switch_model(loaded, pending.config, &mut config, &mut session_settings, &mut tray_app, &mut transcription_state, &mut transcription_worker);
+            }
+        }
+
+        // Keep models whose session was called off, for the next time their profile is used
+        for (model, loaded) in profile_loads.take_abandoned() {
+            match loaded {
+                Ok(ctx) => transcription_worker.keep_model(&model, ctx),
+                Err(e) => eprintln!("✗ Failed to load model {}: {:#}", model, e),
+            }
+        }
+
+        // Start a profile's session once its model is loaded
+        if let SessionPhase::Idle = phase {
+            if let Some(loaded) = profile_loads.session.as_ref().and_then(|pending| pending.model.poll()) {
+                let pending = profile_loads.session.take().unwrap();
+                match loaded.and_then(|ctx| transcription_worker.use_context(ctx, &pending.model.config)) {
+                    Ok(()) => {
+                        use_profile(pending.profile, &config, &mut session_settings, &mut transcription_state);
+                        begin_session(&mut phase, &audio_capture, &mut tray_app, &mut transcription_state);
+                    }
+                    Err(e) => {
+                        eprintln!("✗ Failed to load model {}: {:#}", pending.model.config.model, e);
+                        tray_app.show_warning(&format!("Could not load model {}", pending.model.config.model));
+                    }
                 }
-                Action::NoAction => {}
-                _ => {} // SubmitVadRequest, SubmitLiveRequest handled in audio processing
             }
         }
 
@@ -655,38 +867,101 @@ fn run_app() -> Result<()> {
             match event {
                 HotkeyEvent::StartTranscription => {
                     println!("Hotkey: Starting transcription...");
-                    start_transcription(&audio_capture, &mut tray_app);
-                    transcription_state.reset();
+                    if select_profile(None, &config, phase, &mut session_settings, &mut profile_loads, &mut transcription_state, &mut transcription_worker) {
+                        begin_session(&mut phase, &audio_capture, &mut tray_app, &mut transcription_state);
+                    }
                 }
                 HotkeyEvent::StopTranscription => {
                     println!("Hotkey: Stopping transcription...");
-                    stop_transcription(&audio_capture, &mut tray_app, streaming_mode);
+                    push_to_talk.reset();
+                    profile_loads.cancel(&config);
+                    end_session(&mut phase, &audio_capture, &mut tray_app, streaming_mode, &mut transcription_state, &transcription_worker);
                 }
                 HotkeyEvent::ToggleTranscription => {
-                    let is_recording = audio_capture.lock().unwrap().is_recording();
-                    if is_recording {
+                    push_to_talk.reset();
+                    if let SessionPhase::Recording | SessionPhase::Paused = phase {
                         println!("Hotkey: Toggle - stopping transcription...");
-                        stop_transcription(&audio_capture, &mut tray_app, streaming_mode);
+                        end_session(&mut phase, &audio_capture, &mut tray_app, streaming_mode, &mut transcription_state, &transcription_worker);
                     } else {
                         println!("Hotkey: Toggle - starting transcription...");
-                        start_transcription(&audio_capture, &mut tray_app);
-                        transcription_state.reset();
+                        if select_profile(None, &config, phase, &mut session_settings, &mut profile_loads, &mut transcription_state, &mut transcription_worker) {
+                            begin_session(&mut phase, &audio_capture, &mut tray_app, &mut transcription_state);
+                        }
+                    }
+                }
+                HotkeyEvent::PushToTalkPressed => {
+                    let session_running = !matches!(phase, SessionPhase::Idle);
+                    if push_to_talk.press(session_running) {
+                        println!("Hotkey: Push-to-talk - starting transcription...");
+                        if select_profile(None, &config, phase, &mut session_settings, &mut profile_loads, &mut transcription_state, &mut transcription_worker) {
+                            begin_session(&mut phase, &audio_capture, &mut tray_app, &mut transcription_state);
+                        }
+                        if !matches!(phase, SessionPhase::Recording) {
+                            push_to_talk.reset();
+                        }
+                    }
+                }
+                HotkeyEvent::PushToTalkReleased => {
+                    push_to_talk.release(std::time::Instant::now());
+                }
+                HotkeyEvent::PauseTranscription => {
+                    println!("Hotkey: Pause/resume transcription...");
+                    toggle_pause(&mut phase, &audio_capture, &mut tray_app, streaming_mode, &mut transcription_state, &transcription_worker);
+                }
+                HotkeyEvent::CancelTranscription => {
+                    println!("Hotkey: Cancelling transcription...");
+                    push_to_talk.reset();
+                    profile_loads.cancel(&config);
+                    cancel_session(&mut phase, &audio_capture, &mut tray_app, &mut transcription_state, &transcription_worker, output_sink.as_mut(), session_settings.output_mode);
+                }
+                HotkeyEvent::ToggleProfile(index) => {
+                    push_to_talk.reset();
+                    if let SessionPhase::Recording | SessionPhase::Paused = phase {
+                        println!("Hotkey: Profile - stopping transcription...");
+                        end_session(&mut phase, &audio_capture, &mut tray_app, streaming_mode, &mut transcription_state, &transcription_worker);
+                    } else if profile_loads.is_waiting_for(Some(index)) {
+                        println!("Hotkey: Profile \"{}\" - cancelling...", config.profiles[index].name);
+                        profile_loads.cancel(&config);
+                    } else {
+                        println!("Hotkey: Profile \"{}\" - starting transcription...", config.profiles[index].name);
+                        if select_profile(Some(index), &config, phase, &mut session_settings, &mut profile_loads, &mut transcription_state, &mut transcription_worker) {
+                            begin_session(&mut phase, &audio_capture, &mut tray_app, &mut transcription_state);
+                        }
                     }
                 }
             }
         }
 
+        // Push-to-talk: stop once the hotkey has been released for the grace period
+        if push_to_talk.poll_stop(std::time::Instant::now()) {
+            println!("Hotkey: Push-to-talk released - stopping transcription...");
+            end_session(&mut phase, &audio_capture, &mut tray_app, streaming_mode, &mut transcription_state, &transcription_worker);
+        }
+
         // Poll tray events
         if let Some(event) = tray_app.poll_event() {
             match event {
                 TrayMenuEvent::StartTranscription => {
                     println!("Menu: Starting transcription...");
-                    start_transcription(&audio_capture, &mut tray_app);
-                    transcription_state.reset();
+                    if select_profile(None, &config, phase, &mut session_settings, &mut profile_loads, &mut transcription_state, &mut transcription_worker) {
+                        begin_session(&mut phase, &audio_capture, &mut tray_app, &mut transcription_state);
+                    }
                 }
                 TrayMenuEvent::StopTranscription => {
                     println!("Menu: Stopping transcription...");
-                    stop_transcription(&audio_capture, &mut tray_app, streaming_mode);
+                    push_to_talk.reset();
+                    profile_loads.cancel(&config);
+                    end_session(&mut phase, &audio_capture, &mut tray_app, streaming_mode, &mut transcription_state, &transcription_worker);
+                }
+                TrayMenuEvent::PauseTranscription => {
+                    println!("Menu: Pause/resume transcription...");
+                    toggle_pause(&mut phase, &audio_capture, &mut tray_app, streaming_mode, &mut transcription_state, &transcription_worker);
+                }
+                TrayMenuEvent::SwitchModel(model) => {
+                    println!("Menu: Switching to model {}...", model);
+                    // Keep the check mark on the model in use until the new one is loaded
+                    tray_app.set_current_model(&config.transcription.model);
+                    load_model(model, &config, &mut pending_model);
                 }
                 TrayMenuEvent::Settings => {
                     println!("Opening settings...");
@@ -708,6 +983,23 @@ fn run_app() -> Result<()> {
             }
         }
 
+        // Drain the audio ring buffer, and reattach to another microphone if the current
+        // one was unplugged
+        if is_recording {
+            let recovery = {
+                let mut capture = audio_capture.lock().unwrap();
+                capture.collect_input();
+                capture.recover_if_disconnected()
+            };
+            if let Err(e) = recovery {
+                eprintln!("✗ {:#}", e);
+                end_session(&mut phase, &audio_capture, &mut tray_app, streaming_mode, &mut transcription_state, &transcription_worker);
+            }
+        } else {
+            // Always-on capture: keep only the pre-roll while idle
+            audio_capture.lock().unwrap().trim_pre_roll();
+        }
+
         // Hybrid VAD + live preview streaming
         if streaming_mode {
             let capture_guard = audio_capture.lock().unwrap();
@@ -728,19 +1020,7 @@ fn run_app() -> Result<()> {
 
                     // Execute transcription actions
                     for action in actions {
-                        match action {
-                            Action::SubmitVadRequest { audio, request_id } => {
-                                transcription_worker.transcribe_vad_commit_with_id(audio, request_id);
-                            }
-                            Action::SubmitLiveRequest { audio, request_id } => {
-                                transcription_worker.transcribe_live_preview_with_id(audio, request_id);
-                            }
-                            Action::CancelLiveRequest => {
-                                // Cancel any pending live preview - VAD commit supersedes it
-                                transcription_worker.cancel_all_live_before(u64::MAX);
-                            }
-                            _ => {} // Keyboard actions handled in result processing
-                        }
+                        submit_to_worker(&transcription_worker, action);
                     }
                 }
             }
@@ -748,46 +1028,417 @@ fn run_app() -> Result<()> {
     });
 }
 
-fn start_transcription(audio_capture: &Arc<Mutex<AudioCapture>>, tray_app: &mut TrayApp) {
-    let mut capture = audio_capture.lock().unwrap();
-    if !capture.is_recording() {
-        match capture.start_recording() {
-            Ok(_) => {
-                println!("✓ Recording started");
-                tray_app.set_transcribing(true);
+/// Where the app is in a recording session
+#[derive(Clone, Copy)]
+enum SessionPhase {
+    Idle,
+    Recording,
+    /// Recording is off, but the session (and its text) continues on resume
+    Paused,
+    /// Recording has stopped; waiting for the remaining transcriptions, however long they take
+    Finalizing { since: std::time::Instant, stall_reported: bool },
+}
+
+/// A session waiting for its profile's model to load
+struct PendingSession {
+    /// Index into `Config::profiles`; `None` = the top-level settings
+    profile: Option<usize>,
+    model: PendingModel,
+}
+
+/// Profile models loading in the background
+#[derive(Default)]
+struct ProfileLoads {
+    /// Session waiting for its profile's model to load
+    session: Option<PendingSession>,
+    /// Models no session waits for anymore; they're kept once loaded, for later sessions
+    abandoned: Vec<PendingModel>,
+}
+
+impl ProfileLoads {
+    /// Wait for a profile's model before starting its session, replacing the session that
+    /// was waiting; a model that's already loading isn't loaded twice
+    fn wait_for(&mut self, profile: Option<usize>, settings: TranscriptionConfig, config: &Config) {
+        self.cancel(config);
+        println!("⏳ Loading model {} for {} in the background...", settings.model, profile_name(config, profile));
+        let model = match self.abandoned.iter().position(|pending| pending.config.model == settings.model) {
+            Some(index) => {
+                // Profiles sharing a model can still differ in language
+                let mut model = self.abandoned.swap_remove(index);
+                model.config = settings;
+                model
             }
-            Err(e) => {
-                eprintln!("✗ Failed to start recording: {}", e);
+            None => PendingModel::load(settings),
+        };
+        self.session = Some(PendingSession { profile, model });
+    }
+
+    fn is_waiting_for(&self, profile: Option<usize>) -> bool {
+        self.session.as_ref().is_some_and(|pending| pending.profile == profile)
+    }
+
+    /// Stop waiting for a profile's model; it keeps loading for later sessions
+    fn cancel(&mut self, config: &Config) {
+        if let Some(pending) = self.session.take() {
+            println!("✗ Not starting {}; model {} keeps loading in the background", profile_name(config, pending.profile), pending.model.config.model);
+            self.abandoned.push(pending.model);
+        }
+    }
+
+    /// Models that finished loading (or failed to) after their session was called off
+    fn take_abandoned(&mut self) -> Vec<(String, Result<Arc<whisper_rs::WhisperContext>>)> {
+        let mut finished = Vec::new();
+        self.abandoned.retain(|pending| match pending.poll() {
+            Some(loaded) => {
+                finished.push((pending.config.model.clone(), loaded));
+                false
             }
+            None => true,
+        });
+        finished
+    }
+}
+
+/// Settings that can differ per profile and are read while a session runs
+struct SessionSettings {
+    /// Index into `Config::profiles`; `None` = the top-level settings
+    profile: Option<usize>,
+    output_mode: OutputMode,
+}
+
+/// Start recording a new session (ignored while the previous one is still finalizing)
+///
+/// A paused session is resumed instead.
+fn begin_session(
+    phase: &mut SessionPhase,
+    audio_capture: &Arc<Mutex<AudioCapture>>,
+    tray_app: &mut TrayApp,
+    transcription_state: &mut TranscriptionState,
+) {
+    match phase {
+        SessionPhase::Finalizing { .. } => {
+            println!("⏳ Still transcribing the previous recording, try again in a moment");
+            return;
         }
+        SessionPhase::Paused => {
+            resume_session(phase, audio_capture, tray_app, transcription_state);
+            return;
+        }
+        _ => {}
+    }
+
+    if start_transcription(audio_capture, tray_app) {
+        transcription_state.reset();
+        transcription_state.set_pre_roll(audio_capture.lock().unwrap().take_pre_roll());
+        *phase = SessionPhase::Recording;
     }
 }
 
-fn stop_transcription(
+/// Start loading a model in the background, replacing any model that's still loading
+fn load_model(model: String, config: &Config, pending_model: &mut Option<PendingModel>) {
+    if model == config.transcription.model {
+        // Also drops another model that's still loading
+        *pending_model = None;
+        println!("✓ Using model {}", model);
+        return;
+    }
+    if pending_model.as_ref().is_some_and(|pending| pending.config.model == model) {
+        println!("⏳ Model {} is already loading", model);
+        return;
+    }
+
+    println!("🔄 Loading model {} in the background...", model);
+    *pending_model = Some(PendingModel::load(TranscriptionConfig {
+        model,
+        ..config.transcription.clone()
+    }));
+}
+
+/// Make a model loaded in the background the model in use
+///
+/// Called between sessions. The next session uses the top-level settings with the new
+/// model; if loading failed, the current model stays in use.
+fn switch_model(
+    loaded: Result<Arc<whisper_rs::WhisperContext>>,
+    settings: TranscriptionConfig,
+    config: &mut Config,
+    session_settings: &mut SessionSettings,
+    tray_app: &mut TrayApp,
+    transcription_state: &mut TranscriptionState,
+    transcription_worker: &mut TranscriptionWorker,
+) {
+    let switched = loaded.and_then(|ctx| transcription_worker.use_context(ctx, &settings));
+    if let Err(e) = switched {
+        eprintln!("✗ Failed to load model {}: {:#}", settings.model, e);
+        tray_app.show_warning(&format!("Could not load model {}", settings.model));
+        return;
+    }
+
+    // The previous model is freed unless a profile uses it
+    config.transcription.model = settings.model;
+    let profile_models: Vec<&str> = config.profiles.iter().filter_map(|profile| profile.model.as_deref()).collect();
+    transcription_worker.release_models(&profile_models);
+
+    use_profile(None, config, session_settings, transcription_state);
+    tray_app.set_current_model(&config.transcription.model);
+    println!("✓ Switched to model {}", config.transcription.model);
+}
+
+/// Switch to a profile's settings (`None` = the top-level settings) before a new session
+///
+/// Settings only change between sessions; a paused session is resumed with its own. Returns
+/// false if the session can't start yet: a profile model that isn't loaded is loaded in the
+/// background, and the session starts once it's ready. Starting another session instead
+/// calls off the one that's waiting.
+fn select_profile(
+    profile: Option<usize>,
+    config: &Config,
+    phase: SessionPhase,
+    session_settings: &mut SessionSettings,
+    profile_loads: &mut ProfileLoads,
+    transcription_state: &mut TranscriptionState,
+    transcription_worker: &mut TranscriptionWorker,
+) -> bool {
+    if !matches!(phase, SessionPhase::Idle) {
+        return true;
+    }
+    profile_loads.cancel(config);
+    if profile == session_settings.profile {
+        return true;
+    }
+
+    let settings = config.for_profile(profile);
+    match transcription_worker.reconfigure(&settings.transcription) {
+        Ok(true) => {}
+        Ok(false) => {
+            profile_loads.wait_for(profile, settings.transcription, config);
+            return false;
+        }
+        Err(e) => {
+            eprintln!("✗ Failed to switch to {}: {:#}", profile_name(config, profile), e);
+            return false;
+        }
+    }
+
+    use_profile(profile, config, session_settings, transcription_state);
+    true
+}
+
+/// Apply the rest of a profile's settings, once the worker uses its model
+fn use_profile(
+    profile: Option<usize>,
+    config: &Config,
+    session_settings: &mut SessionSettings,
+    transcription_state: &mut TranscriptionState,
+) {
+    let settings = config.for_profile(profile);
+    transcription_state.reconfigure(&settings.transcription);
+    *session_settings = SessionSettings {
+        profile,
+        output_mode: settings.output.mode,
+    };
+    println!("🔀 Using {}", profile_name(config, profile));
+}
+
+fn profile_name(config: &Config, profile: Option<usize>) -> String {
+    match profile {
+        Some(index) => format!("profile \"{}\"", config.profiles[index].name),
+        None => "the default settings".to_string(),
+    }
+}
+
+/// Stop recording and submit the rest of the session for transcription
+fn end_session(
+    phase: &mut SessionPhase,
     audio_capture: &Arc<Mutex<AudioCapture>>,
     tray_app: &mut TrayApp,
     streaming_mode: bool,
+    transcription_state: &mut TranscriptionState,
+    transcription_worker: &TranscriptionWorker,
 ) {
-    let mut capture = audio_capture.lock().unwrap();
-    if capture.is_recording() {
-        match capture.stop_recording() {
-            Ok(_audio_data) => {
-                println!("✓ Recording stopped");
-                tray_app.set_transcribing(false);
+    // A paused session already flushed its last utterance
+    let audio = if let SessionPhase::Paused = phase {
+        tray_app.set_transcribing(false);
+        Vec::new()
+    } else {
+        let Some(audio) = stop_transcription(audio_capture, tray_app) else {
+            return;
+        };
+        audio
+    };
+
+    // Streaming: commit the utterance in progress. Otherwise: transcribe the whole recording.
+    let actions = if streaming_mode {
+        transcription_state.finish(&audio)
+    } else {
+        transcription_state.submit_recording(audio).into_iter().collect()
+    };
+    for action in actions {
+        submit_to_worker(transcription_worker, action);
+    }
+
+    println!("⏳ Finalizing transcription...");
+    *phase = SessionPhase::Finalizing { since: std::time::Instant::now(), stall_reported: false };
+}
+
+/// Pause a recording session, or resume a paused one
+fn toggle_pause(
+    phase: &mut SessionPhase,
+    audio_capture: &Arc<Mutex<AudioCapture>>,
+    tray_app: &mut TrayApp,
+    streaming_mode: bool,
+    transcription_state: &mut TranscriptionState,
+    transcription_worker: &TranscriptionWorker,
+) {
+    match phase {
+        SessionPhase::Recording => {
+            pause_session(phase, audio_capture, tray_app, streaming_mode, transcription_state, transcription_worker)
+        }
+        SessionPhase::Paused => resume_session(phase, audio_capture, tray_app, transcription_state),
+        _ => {}
+    }
+}
 
-                // In streaming mode, we already typed everything, so just finish
-                println!("Streaming transcription complete");
+/// Stop recording and flush the utterance in progress, keeping the session's text
+fn pause_session(
+    phase: &mut SessionPhase,
+    audio_capture: &Arc<Mutex<AudioCapture>>,
+    tray_app: &mut TrayApp,
+    streaming_mode: bool,
+    transcription_state: &mut TranscriptionState,
+    transcription_worker: &TranscriptionWorker,
+) {
+    let Some(audio) = stop_transcription(audio_capture, tray_app) else {
+        return;
+    };
 
-                // TODO: Non-streaming mode would need access to transcriber
-                // For now, only streaming mode is fully supported with the threaded architecture
-                if !streaming_mode {
-                    println!("⚠️  Non-streaming mode not yet supported with threaded transcription");
+    // Non-streaming: transcribe the part recorded so far; its text is followed by the rest
+    let actions = if streaming_mode {
+        transcription_state.pause(&audio)
+    } else {
+        transcription_state.submit_recording(audio).into_iter().collect()
+    };
+    for action in actions {
+        submit_to_worker(transcription_worker, action);
+    }
+
+    tray_app.set_paused();
+    println!("⏸️  Paused");
+    *phase = SessionPhase::Paused;
+}
+
+/// Continue recording a paused session where it left off
+fn resume_session(
+    phase: &mut SessionPhase,
+    audio_capture: &Arc<Mutex<AudioCapture>>,
+    tray_app: &mut TrayApp,
+    transcription_state: &mut TranscriptionState,
+) {
+    if start_transcription(audio_capture, tray_app) {
+        transcription_state.set_pre_roll(audio_capture.lock().unwrap().take_pre_roll());
+        println!("▶️  Resumed");
+        *phase = SessionPhase::Recording;
+    }
+}
+
+/// Discard the session: stop recording, cancel its transcriptions and erase its text
+fn cancel_session(
+    phase: &mut SessionPhase,
+    audio_capture: &Arc<Mutex<AudioCapture>>,
+    tray_app: &mut TrayApp,
+    transcription_state: &mut TranscriptionState,
+    transcription_worker: &TranscriptionWorker,
+    output_sink: &mut dyn OutputSink,
+    output_mode: OutputMode,
+) {
+    if let SessionPhase::Idle = phase {
+        return;
+    }
+
+    // The captured audio is thrown away with the rest of the session
+    let _ = stop_transcription(audio_capture, tray_app);
+    tray_app.set_transcribing(false); // Also when paused
+
+    for action in transcription_state.cancel() {
+        match action {
+            Action::ReplaceText { .. } => {
+                if output_mode.types() {
+                    if let Err(e) = output_sink.apply(&action) {
+                        eprintln!("✗ Failed to erase text: {}", e);
+                    }
                 }
             }
-            Err(e) => {
-                eprintln!("✗ Failed to stop recording: {}", e);
-                tray_app.set_transcribing(false);
-            }
+            other => submit_to_worker(transcription_worker, other),
+        }
+    }
+
+    println!("🗑️  Session cancelled");
+    *phase = SessionPhase::Idle;
+}
+
+/// Start recording, returning true if a new recording was started
+fn start_transcription(audio_capture: &Arc<Mutex<AudioCapture>>, tray_app: &mut TrayApp) -> bool {
+    let mut capture = audio_capture.lock().unwrap();
+    if capture.is_recording() {
+        return false;
+    }
+
+    match capture.start_recording() {
+        Ok(_) => {
+            println!("✓ Recording started");
+            tray_app.set_transcribing(true);
+            true
+        }
+        Err(e) => {
+            eprintln!("✗ Failed to start recording: {}", e);
+            false
+        }
+    }
+}
+
+/// Stop recording and return the captured audio
+fn stop_transcription(
+    audio_capture: &Arc<Mutex<AudioCapture>>,
+    tray_app: &mut TrayApp,
+) -> Option<Vec<f32>> {
+    let mut capture = audio_capture.lock().unwrap();
+    if !capture.is_recording() {
+        return None;
+    }
+
+    match capture.stop_recording() {
+        Ok(audio_data) => {
+            println!("✓ Recording stopped");
+            tray_app.set_transcribing(false);
+            Some(audio_data)
+        }
+        Err(e) => {
+            eprintln!("✗ Failed to stop recording: {}", e);
+            tray_app.set_transcribing(false);
+            None
+        }
+    }
+}
+
+/// Forward a transcription action from the state machine to the worker threads
+fn submit_to_worker(transcription_worker: &TranscriptionWorker, action: Action) {
+    match action {
+        Action::SubmitVadRequest { audio, request_id } => {
+            transcription_worker.transcribe_vad_commit_with_id(audio, request_id);
+        }
+        Action::RetryVadRequest { audio, request_id, use_fallback } => {
+            transcription_worker.retry_vad_commit_with_id(audio, request_id, use_fallback);
+        }
+        Action::SubmitLiveRequest { audio, request_id } => {
+            transcription_worker.transcribe_live_preview_with_id(audio, request_id);
+        }
+        Action::CancelLiveRequest { request_id } => {
+            // Cancel the pending live preview (and anything older) - VAD commit supersedes it
+            transcription_worker.cancel_all_live_before(request_id + 1);
+        }
+        Action::CancelAllRequests { before } => {
+            transcription_worker.cancel_all_before(before);
         }
+        _ => {} // Keyboard actions handled in result processing
     }
 }
@@ -130,6 +130,25 @@ impl ModelDownloader {
         Ok(())
     }
 
+    /// Names of the models in the models directory, sorted
+    pub fn installed_models(&self) -> Vec<String> {
+        let Ok(entries) = fs::read_dir(&self.models_dir) else {
+            return Vec::new();
+        };
+        let mut models: Vec<String> = entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let file_name = entry.file_name().to_string_lossy().into_owned();
+                file_name
+                    .strip_prefix("ggml-")
+                    .and_then(|name| name.strip_suffix(".bin"))
+                    .map(|name| name.to_string())
+            })
+            .collect();
+        models.sort();
+        models
+    }
+
     pub fn list_available_models() -> Vec<(&'static str, &'static str, &'static str)> {
         vec![
             ("tiny.en", "~75MB", "Fastest, good quality"),
@@ -0,0 +1,267 @@
+//! Output sinks for transcribed text
+//!
+//! The event loop hands every keyboard action produced by the transcription state machine
+//! to an `OutputSink`. This keeps the loop independent of where text ends up: typed into
+//! the focused application, printed to stdout, mirrored into a file, or recorded in memory
+//! for tests.
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::config::{OutputConfig, OutputSinkKind};
+use crate::keyboard;
+use crate::transcription_state::Action;
+
+/// Destination for `Action::AppendText` and `Action::ReplaceText`
+pub trait OutputSink {
+    /// Append text after whatever the sink currently shows
+    fn append_text(&mut self, text: &str) -> Result<()>;
+
+    /// Delete `chars_to_delete` characters from the end, then type `new_text`
+    fn replace_text(&mut self, chars_to_delete: usize, new_text: &str) -> Result<()>;
+
+    /// Apply a state machine action
+    ///
+    /// Actions that are meant for the transcription workers are ignored.
+    fn apply(&mut self, action: &Action) -> Result<()> {
+        match action {
+            Action::AppendText(text) => self.append_text(text),
+            Action::ReplaceText { chars_to_delete, new_text } => {
+                self.replace_text(*chars_to_delete, new_text)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Apply a delete-then-type edit to an in-memory copy of the output
+///
+/// Deletes whole characters (not bytes), matching what backspace does on screen.
+pub fn apply_edit(text: &mut String, chars_to_delete: usize, new_text: &str) {
+    if chars_to_delete > 0 {
+        let keep_chars = text.chars().count().saturating_sub(chars_to_delete);
+        let keep_bytes = text
+            .char_indices()
+            .nth(keep_chars)
+            .map(|(i, _)| i)
+            .unwrap_or(text.len());
+        text.truncate(keep_bytes);
+    }
+    text.push_str(new_text);
+}
+
+/// Types text into the focused application via keyboard injection
+pub struct KeyboardSink;
+
+impl OutputSink for KeyboardSink {
+    fn append_text(&mut self, text: &str) -> Result<()> {
+        keyboard::append_text(text);
+        Ok(())
+    }
+
+    fn replace_text(&mut self, chars_to_delete: usize, new_text: &str) -> Result<()> {
+        keyboard::replace_text_with_backspace(chars_to_delete, new_text);
+        Ok(())
+    }
+}
+
+/// Prints the current output to stdout after every change
+#[derive(Default)]
+pub struct StdoutSink {
+    text: String,
+}
+
+impl StdoutSink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn print(&self) {
+        println!("📝 Output: \"{}\"", self.text);
+    }
+}
+
+impl OutputSink for StdoutSink {
+    fn append_text(&mut self, text: &str) -> Result<()> {
+        apply_edit(&mut self.text, 0, text);
+        self.print();
+        Ok(())
+    }
+
+    fn replace_text(&mut self, chars_to_delete: usize, new_text: &str) -> Result<()> {
+        apply_edit(&mut self.text, chars_to_delete, new_text);
+        self.print();
+        Ok(())
+    }
+}
+
+/// Keeps a file in sync with the current output
+///
+/// The file is rewritten on every change, so deletions are reflected as well.
+pub struct FileSink {
+    path: PathBuf,
+    text: String,
+}
+
+impl FileSink {
+    /// Create a sink writing to `path`, truncating any existing contents
+    pub fn new(path: PathBuf) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            if !parent.as_os_str().is_empty() {
+                fs::create_dir_all(parent)
+                    .with_context(|| format!("Failed to create directory {}", parent.display()))?;
+            }
+        }
+
+        let sink = FileSink {
+            path,
+            text: String::new(),
+        };
+        sink.flush()?;
+        Ok(sink)
+    }
+
+    fn flush(&self) -> Result<()> {
+        fs::write(&self.path, &self.text)
+            .with_context(|| format!("Failed to write output file {}", self.path.display()))
+    }
+}
+
+impl OutputSink for FileSink {
+    fn append_text(&mut self, text: &str) -> Result<()> {
+        apply_edit(&mut self.text, 0, text);
+        self.flush()
+    }
+
+    fn replace_text(&mut self, chars_to_delete: usize, new_text: &str) -> Result<()> {
+        apply_edit(&mut self.text, chars_to_delete, new_text);
+        self.flush()
+    }
+}
+
+/// Records every action in memory (for tests and simulations)
+#[derive(Default)]
+pub struct RecorderSink {
+    actions: Vec<Action>,
+    text: String,
+}
+
+impl RecorderSink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// All keyboard actions received so far, in order
+    pub fn actions(&self) -> &[Action] {
+        &self.actions
+    }
+
+    /// What would currently be on screen after replaying all actions
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+impl OutputSink for RecorderSink {
+    fn append_text(&mut self, text: &str) -> Result<()> {
+        self.actions.push(Action::AppendText(text.to_string()));
+        apply_edit(&mut self.text, 0, text);
+        Ok(())
+    }
+
+    fn replace_text(&mut self, chars_to_delete: usize, new_text: &str) -> Result<()> {
+        self.actions.push(Action::ReplaceText {
+            chars_to_delete,
+            new_text: new_text.to_string(),
+        });
+        apply_edit(&mut self.text, chars_to_delete, new_text);
+        Ok(())
+    }
+}
+
+/// Build the output sink selected in the configuration
+pub fn build_sink(config: &OutputConfig) -> Result<Box<dyn OutputSink>> {
+    match config.sink {
+        OutputSinkKind::Keyboard => Ok(Box::new(KeyboardSink)),
+        OutputSinkKind::Stdout => Ok(Box::new(StdoutSink::new())),
+        OutputSinkKind::File => {
+            let path = config
+                .file
+                .clone()
+                .context("output.file must be set when output.sink is \"file\"")?;
+            Ok(Box::new(FileSink::new(expand_home(&path)?)?))
+        }
+    }
+}
+
+/// Replace a leading `~` with the home directory, as a shell would
+fn expand_home(path: &Path) -> Result<PathBuf> {
+    match path.strip_prefix("~") {
+        Ok(rest) => Ok(dirs::home_dir()
+            .context("Failed to get home directory")?
+            .join(rest)),
+        Err(_) => Ok(path.to_path_buf()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_edit_deletes_characters_not_bytes() {
+        let mut text = "café 😀".to_string();
+        apply_edit(&mut text, 2, "!");
+        assert_eq!(text, "café!");
+    }
+
+    #[test]
+    fn test_apply_edit_delete_more_than_available() {
+        let mut text = "hi".to_string();
+        apply_edit(&mut text, 10, "there");
+        assert_eq!(text, "there");
+    }
+
+    #[test]
+    fn test_recorder_replays_actions() {
+        let mut sink = RecorderSink::new();
+        sink.apply(&Action::AppendText("Hello world".to_string())).unwrap();
+        sink.apply(&Action::ReplaceText {
+            chars_to_delete: 5,
+            new_text: "there ".to_string(),
+        })
+        .unwrap();
+        sink.apply(&Action::NoAction).unwrap();
+
+        assert_eq!(sink.text(), "Hello there ");
+        assert_eq!(sink.actions().len(), 2);
+    }
+
+    #[test]
+    fn test_file_path_expands_home() {
+        let home = dirs::home_dir().unwrap();
+        assert_eq!(
+            expand_home(Path::new("~/.live-transcribe/transcript.txt")).unwrap(),
+            home.join(".live-transcribe/transcript.txt")
+        );
+        assert_eq!(expand_home(Path::new("~")).unwrap(), home);
+        assert_eq!(
+            expand_home(Path::new("/tmp/transcript.txt")).unwrap(),
+            PathBuf::from("/tmp/transcript.txt")
+        );
+        assert_eq!(expand_home(Path::new("~other/notes.txt")).unwrap(), PathBuf::from("~other/notes.txt"));
+    }
+
+    #[test]
+    fn test_file_sink_mirrors_output() {
+        let path = std::env::temp_dir()
+            .join(format!("live-transcribe-output-{}.txt", std::process::id()));
+
+        let mut sink = FileSink::new(path.clone()).unwrap();
+        sink.append_text("Hello world").unwrap();
+        sink.replace_text(5, "there").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "Hello there");
+        let _ = fs::remove_file(&path);
+    }
+}
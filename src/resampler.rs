@@ -0,0 +1,304 @@
+//! Band-limited sample rate conversion
+//!
+//! Converts microphone audio (typically 44.1kHz or 48kHz) to the 16kHz Whisper expects.
+//! Uses a Kaiser-windowed sinc filter evaluated as a polyphase filter bank, so content
+//! above the output Nyquist frequency is removed instead of folding back into the speech
+//! band. The resampler is stateful: feeding audio in chunks produces exactly the same
+//! output as feeding it all at once, so chunk boundaries don't click.
+
+/// Zero crossings of the sinc on each side of the filter center
+const ZERO_CROSSINGS: usize = 32;
+
+/// Filter cutoff relative to the output Nyquist frequency
+///
+/// Leaves room for the transition band so it ends before the output Nyquist frequency.
+const ROLLOFF: f64 = 0.92;
+
+/// Kaiser window shape (~80dB stopband attenuation)
+const KAISER_BETA: f64 = 8.0;
+
+pub struct Resampler {
+    from_rate: u32,
+    to_rate: u32,
+    /// Input samples advanced per output sample, as the fraction `step / phases`
+    step: usize,
+    phases: usize,
+    /// Filter taps on each side of the center sample
+    half_taps: usize,
+    /// `phases` rows of `2 * half_taps` taps
+    taps: Vec<f32>,
+    /// Input history; `buffer[center]` is the input sample at or before the next output
+    buffer: Vec<f32>,
+    center: usize,
+    /// Fractional position of the next output between `center` and `center + 1`, in 1/phases
+    phase: usize,
+    /// Total input samples received and input position of the next output (for `flush`)
+    input_count: u64,
+    center_position: u64,
+}
+
+impl Resampler {
+    pub fn new(from_rate: u32, to_rate: u32) -> Self {
+        let divisor = gcd(from_rate as usize, to_rate as usize).max(1);
+        let step = from_rate as usize / divisor;
+        let phases = to_rate as usize / divisor;
+
+        // Cutoff in cycles per input sample (0.5 = input Nyquist)
+        let cutoff = 0.5 * ROLLOFF * (to_rate as f64 / from_rate as f64).min(1.0);
+        let half_width = ZERO_CROSSINGS as f64 / (2.0 * cutoff);
+        let half_taps = half_width.ceil() as usize;
+        let taps = build_taps(phases, half_taps, cutoff, half_width);
+
+        let mut resampler = Resampler {
+            from_rate,
+            to_rate,
+            step,
+            phases,
+            half_taps,
+            taps,
+            buffer: Vec::new(),
+            center: 0,
+            phase: 0,
+            input_count: 0,
+            center_position: 0,
+        };
+        resampler.reset();
+        resampler
+    }
+
+    pub fn from_rate(&self) -> u32 {
+        self.from_rate
+    }
+
+    pub fn to_rate(&self) -> u32 {
+        self.to_rate
+    }
+
+    /// Forget all buffered audio (call when a new recording starts)
+    pub fn reset(&mut self) {
+        // Silence before the first sample
+        self.buffer.clear();
+        self.buffer.resize(self.half_taps.saturating_sub(1), 0.0);
+        self.center = self.buffer.len();
+        self.phase = 0;
+        self.input_count = 0;
+        self.center_position = 0;
+    }
+
+    /// Resample the next block of input
+    ///
+    /// Output for the last few input samples is held back until more input arrives
+    /// (or `flush` is called), because the filter needs samples on both sides.
+    pub fn process(&mut self, input: &[f32]) -> Vec<f32> {
+        if self.from_rate == self.to_rate {
+            return input.to_vec();
+        }
+
+        self.buffer.extend_from_slice(input);
+        self.input_count += input.len() as u64;
+        self.drain_output(u64::MAX)
+    }
+
+    /// Return the remaining output at the end of a recording and reset
+    pub fn flush(&mut self) -> Vec<f32> {
+        if self.from_rate == self.to_rate {
+            return Vec::new();
+        }
+
+        // Pad with silence so the last input samples get a full filter window,
+        // but only produce output up to the end of the real input
+        let input_end = self.input_count;
+        self.buffer.resize(self.buffer.len() + self.half_taps + 1, 0.0);
+        let output = self.drain_output(input_end);
+        self.reset();
+        output
+    }
+
+    fn drain_output(&mut self, input_end: u64) -> Vec<f32> {
+        let window = 2 * self.half_taps;
+        let mut output = Vec::with_capacity(
+            (self.buffer.len() - self.center) * self.phases / self.step + 1,
+        );
+
+        while self.center + self.half_taps < self.buffer.len() && self.center_position < input_end {
+            let start = self.center + 1 - self.half_taps;
+            let samples = &self.buffer[start..start + window];
+            let taps = &self.taps[self.phase * window..(self.phase + 1) * window];
+            output.push(samples.iter().zip(taps).map(|(s, t)| s * t).sum());
+
+            self.phase += self.step;
+            let advance = self.phase / self.phases;
+            self.phase %= self.phases;
+            self.center += advance;
+            self.center_position += advance as u64;
+        }
+
+        // Drop history the filter no longer needs
+        let keep_from = (self.center + 1).saturating_sub(self.half_taps).min(self.buffer.len());
+        self.buffer.drain(..keep_from);
+        self.center -= keep_from;
+
+        output
+    }
+}
+
+/// Polyphase filter bank: row `p` holds the taps for an output `p / phases` of an input
+/// sample after the center, applied to inputs `center + 1 - half_taps ..= center + half_taps`
+fn build_taps(phases: usize, half_taps: usize, cutoff: f64, half_width: f64) -> Vec<f32> {
+    let window = 2 * half_taps;
+    let mut taps = Vec::with_capacity(phases * window);
+
+    for phase in 0..phases {
+        let offset = phase as f64 / phases as f64;
+        let row: Vec<f64> = (0..window)
+            .map(|m| {
+                // Distance from the output position to the input sample, in input samples
+                let distance = offset + half_taps as f64 - 1.0 - m as f64;
+                windowed_sinc(distance, cutoff, half_width)
+            })
+            .collect();
+
+        // Unity gain at DC for every phase
+        let sum: f64 = row.iter().sum();
+        taps.extend(row.iter().map(|&t| (t / sum) as f32));
+    }
+
+    taps
+}
+
+fn windowed_sinc(distance: f64, cutoff: f64, half_width: f64) -> f64 {
+    let ratio = distance / half_width;
+    if ratio.abs() >= 1.0 {
+        return 0.0;
+    }
+
+    let x = 2.0 * cutoff * distance;
+    let sinc = if x.abs() < 1e-12 {
+        1.0
+    } else {
+        (std::f64::consts::PI * x).sin() / (std::f64::consts::PI * x)
+    };
+    let kaiser = bessel_i0(KAISER_BETA * (1.0 - ratio * ratio).sqrt()) / bessel_i0(KAISER_BETA);
+
+    2.0 * cutoff * sinc * kaiser
+}
+
+/// Zeroth-order modified Bessel function of the first kind (power series)
+fn bessel_i0(x: f64) -> f64 {
+    let mut sum = 1.0;
+    let mut term = 1.0;
+    let half_x = x / 2.0;
+    for k in 1..50 {
+        term *= half_x / k as f64;
+        sum += term * term;
+        if term * term < sum * 1e-16 {
+            break;
+        }
+    }
+    sum
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(frequency: f64, rate: u32, samples: usize) -> Vec<f32> {
+        (0..samples)
+            .map(|i| (2.0 * std::f64::consts::PI * frequency * i as f64 / rate as f64).sin() as f32 * 0.5)
+            .collect()
+    }
+
+    /// Linear sweep from `start_hz` to `end_hz`
+    fn sweep(start_hz: f64, end_hz: f64, rate: u32, samples: usize) -> Vec<f32> {
+        let duration = samples as f64 / rate as f64;
+        (0..samples)
+            .map(|i| {
+                let t = i as f64 / rate as f64;
+                let phase = 2.0 * std::f64::consts::PI * (start_hz * t + (end_hz - start_hz) * t * t / (2.0 * duration));
+                phase.sin() as f32 * 0.5
+            })
+            .collect()
+    }
+
+    fn resample_all(input: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
+        let mut resampler = Resampler::new(from_rate, to_rate);
+        let mut output = resampler.process(input);
+        output.extend(resampler.flush());
+        output
+    }
+
+    /// RMS ignoring the filter's start-up and tail
+    fn steady_rms(audio: &[f32]) -> f32 {
+        let trim = audio.len() / 10;
+        crate::vad::rms(&audio[trim..audio.len() - trim])
+    }
+
+    fn db(ratio: f32) -> f32 {
+        20.0 * ratio.log10()
+    }
+
+    #[test]
+    fn test_output_length() {
+        assert_eq!(resample_all(&vec![0.1; 48000], 48000, 16000).len(), 16000);
+        assert_eq!(resample_all(&vec![0.1; 44100], 44100, 16000).len(), 16000);
+        assert_eq!(resample_all(&vec![0.1; 4800], 16000, 16000).len(), 4800);
+    }
+
+    #[test]
+    fn test_passband_preserved() {
+        for &(rate, frequency) in &[(48000, 1000.0), (44100, 3000.0), (48000, 6000.0)] {
+            let input = sine(frequency, rate, rate as usize);
+            let output = resample_all(&input, rate, 16000);
+            let gain = db(steady_rms(&output) / steady_rms(&input));
+            assert!(gain.abs() < 0.1, "{}Hz from {}Hz: {:.2}dB", frequency, rate, gain);
+        }
+    }
+
+    #[test]
+    fn test_aliasing_rejected_for_tones() {
+        // Tones above 8kHz would fold back to 16kHz - f with linear interpolation
+        for &(rate, frequency) in &[(48000, 9000.0), (48000, 12000.0), (48000, 20000.0), (44100, 15000.0)] {
+            let input = sine(frequency, rate, rate as usize);
+            let output = resample_all(&input, rate, 16000);
+            let attenuation = db(steady_rms(&output) / steady_rms(&input));
+            assert!(attenuation < -60.0, "{}Hz from {}Hz: {:.1}dB", frequency, rate, attenuation);
+        }
+    }
+
+    #[test]
+    fn test_aliasing_rejected_for_sweep() {
+        let input = sweep(8500.0, 23000.0, 48000, 96000);
+        let output = resample_all(&input, 48000, 16000);
+        let attenuation = db(steady_rms(&output) / steady_rms(&input));
+        assert!(attenuation < -60.0, "sweep: {:.1}dB", attenuation);
+    }
+
+    #[test]
+    fn test_chunked_matches_one_shot() {
+        let input = sweep(100.0, 7000.0, 44100, 44100);
+        let expected = resample_all(&input, 44100, 16000);
+
+        // Irregular chunk sizes, like audio callbacks and timer-driven chunking produce
+        let mut resampler = Resampler::new(44100, 16000);
+        let mut output = Vec::new();
+        let mut offset = 0;
+        for (i, size) in [441, 1, 13230, 517, 4096].iter().cycle().enumerate() {
+            if offset >= input.len() || i > 1000 {
+                break;
+            }
+            let end = (offset + size).min(input.len());
+            output.extend(resampler.process(&input[offset..end]));
+            offset = end;
+        }
+        output.extend(resampler.flush());
+
+        assert_eq!(output.len(), expected.len());
+        for (a, b) in output.iter().zip(&expected) {
+            assert!((a - b).abs() < 1e-6);
+        }
+    }
+}
@@ -0,0 +1,193 @@
+//! Lock-free single-producer/single-consumer ring buffer for audio samples
+//!
+//! The audio callback runs on a real-time thread and must never block, so it pushes
+//! samples into this ring instead of locking a shared `Vec`. The event loop drains it.
+//! When the ring is full, new samples are dropped and counted rather than blocking
+//! the producer, so overruns can be reported.
+
+use std::cell::UnsafeCell;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+struct Ring {
+    slots: Box<[UnsafeCell<f32>]>,
+    /// Total samples written (producer position)
+    head: AtomicUsize,
+    /// Total samples read (consumer position)
+    tail: AtomicUsize,
+    /// Samples dropped because the ring was full
+    dropped: AtomicU64,
+}
+
+// Safety: the producer only writes slots between `head` and `tail + capacity`, the consumer
+// only reads slots between `tail` and `head`, and each side publishes its position with
+// release/acquire ordering after touching the slots. `Producer` and `Consumer` are unique
+// handles (not `Clone`), so there is at most one thread on each side.
+unsafe impl Sync for Ring {}
+
+impl Ring {
+    fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+}
+
+/// Create a ring buffer holding up to `capacity` samples
+pub fn ring_buffer(capacity: usize) -> (Producer, Consumer) {
+    let capacity = capacity.max(1);
+    let ring = Arc::new(Ring {
+        slots: (0..capacity).map(|_| UnsafeCell::new(0.0)).collect(),
+        head: AtomicUsize::new(0),
+        tail: AtomicUsize::new(0),
+        dropped: AtomicU64::new(0),
+    });
+
+    (
+        Producer { ring: Arc::clone(&ring) },
+        Consumer { ring },
+    )
+}
+
+/// Writing end, owned by the audio callback
+pub struct Producer {
+    ring: Arc<Ring>,
+}
+
+impl Producer {
+    /// Append samples without blocking; samples that don't fit are dropped and counted
+    ///
+    /// Returns the number of samples written.
+    pub fn push(&mut self, samples: impl IntoIterator<Item = f32>) -> usize {
+        let ring = &self.ring;
+        let capacity = ring.capacity();
+        let head = ring.head.load(Ordering::Relaxed);
+        let tail = ring.tail.load(Ordering::Acquire);
+        let free = capacity - head.wrapping_sub(tail);
+
+        let mut written = 0;
+        let mut dropped = 0u64;
+        for sample in samples {
+            if written < free {
+                // Safety: this slot is not visible to the consumer until `head` is published
+                unsafe {
+                    *ring.slots[head.wrapping_add(written) % capacity].get() = sample;
+                }
+                written += 1;
+            } else {
+                dropped += 1;
+            }
+        }
+
+        ring.head.store(head.wrapping_add(written), Ordering::Release);
+        if dropped > 0 {
+            ring.dropped.fetch_add(dropped, Ordering::Relaxed);
+        }
+        written
+    }
+}
+
+/// Reading end, owned by the event loop
+pub struct Consumer {
+    ring: Arc<Ring>,
+}
+
+impl Consumer {
+    /// Move all available samples to the end of `out`, returning how many were moved
+    pub fn pop_into(&mut self, out: &mut Vec<f32>) -> usize {
+        let ring = &self.ring;
+        let capacity = ring.capacity();
+        let tail = ring.tail.load(Ordering::Relaxed);
+        let head = ring.head.load(Ordering::Acquire);
+        let available = head.wrapping_sub(tail);
+
+        out.reserve(available);
+        for i in 0..available {
+            // Safety: the producer doesn't write this slot until `tail` is published
+            out.push(unsafe { *ring.slots[tail.wrapping_add(i) % capacity].get() });
+        }
+
+        ring.tail.store(tail.wrapping_add(available), Ordering::Release);
+        available
+    }
+
+    /// Number of samples waiting to be read
+    pub fn len(&self) -> usize {
+        let head = self.ring.head.load(Ordering::Acquire);
+        head.wrapping_sub(self.ring.tail.load(Ordering::Relaxed))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Total samples dropped because the consumer fell behind
+    pub fn dropped(&self) -> u64 {
+        self.ring.dropped.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fifo_order_across_wraparound() {
+        let (mut producer, mut consumer) = ring_buffer(4);
+        let mut out = Vec::new();
+
+        assert_eq!(producer.push([1.0, 2.0, 3.0]), 3);
+        assert_eq!(consumer.pop_into(&mut out), 3);
+        assert_eq!(producer.push([4.0, 5.0, 6.0]), 3);
+        assert_eq!(consumer.len(), 3);
+        assert_eq!(consumer.pop_into(&mut out), 3);
+
+        assert_eq!(out, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        assert!(consumer.is_empty());
+        assert_eq!(consumer.dropped(), 0);
+    }
+
+    #[test]
+    fn test_overflow_drops_and_counts_new_samples() {
+        let (mut producer, mut consumer) = ring_buffer(4);
+
+        assert_eq!(producer.push([1.0, 2.0, 3.0]), 3);
+        assert_eq!(producer.push([4.0, 5.0, 6.0]), 1);
+        assert_eq!(consumer.dropped(), 2);
+
+        let mut out = Vec::new();
+        consumer.pop_into(&mut out);
+        assert_eq!(out, vec![1.0, 2.0, 3.0, 4.0]);
+
+        // Space is available again after draining
+        assert_eq!(producer.push([7.0]), 1);
+        assert_eq!(consumer.dropped(), 2);
+    }
+
+    #[test]
+    fn test_concurrent_producer_and_consumer() {
+        const TOTAL: usize = 100_000;
+        let (mut producer, mut consumer) = ring_buffer(256);
+
+        let writer = std::thread::spawn(move || {
+            let mut next = 0;
+            while next < TOTAL {
+                let end = (next + 64).min(TOTAL);
+                let written = producer.push((next..end).map(|i| i as f32));
+                // Only the written prefix counts; retry the rest
+                next += written;
+                if written == 0 {
+                    std::thread::yield_now();
+                }
+            }
+        });
+
+        let mut out = Vec::with_capacity(TOTAL);
+        while out.len() < TOTAL {
+            if consumer.pop_into(&mut out) == 0 {
+                std::thread::yield_now();
+            }
+        }
+        writer.join().unwrap();
+
+        assert!(out.iter().enumerate().all(|(i, &sample)| sample == i as f32));
+    }
+}
@@ -0,0 +1,154 @@
+//! Conversion of raw input samples to f32 mono
+//!
+//! Audio devices deliver samples in whatever format they support (i16, u16, i32, f32, ...).
+//! Everything is normalized to f32 in `-1.0..1.0` and downmixed to mono in the stream
+//! callback, before it reaches the resampler.
+
+/// A sample type that can be normalized to f32 in `-1.0..1.0`
+pub trait ToF32: Copy {
+    fn to_f32(self) -> f32;
+}
+
+impl ToF32 for i8 {
+    fn to_f32(self) -> f32 {
+        self as f32 / 128.0
+    }
+}
+
+impl ToF32 for i16 {
+    fn to_f32(self) -> f32 {
+        self as f32 / 32_768.0
+    }
+}
+
+impl ToF32 for i32 {
+    fn to_f32(self) -> f32 {
+        (self as f64 / 2_147_483_648.0) as f32
+    }
+}
+
+impl ToF32 for i64 {
+    fn to_f32(self) -> f32 {
+        (self as f64 / 9_223_372_036_854_775_808.0) as f32
+    }
+}
+
+// Unsigned formats are centered on half their range
+
+impl ToF32 for u8 {
+    fn to_f32(self) -> f32 {
+        (self as f32 - 128.0) / 128.0
+    }
+}
+
+impl ToF32 for u16 {
+    fn to_f32(self) -> f32 {
+        (self as f32 - 32_768.0) / 32_768.0
+    }
+}
+
+impl ToF32 for u32 {
+    fn to_f32(self) -> f32 {
+        ((self as f64 - 2_147_483_648.0) / 2_147_483_648.0) as f32
+    }
+}
+
+impl ToF32 for u64 {
+    fn to_f32(self) -> f32 {
+        ((self as f64 - 9_223_372_036_854_775_808.0) / 9_223_372_036_854_775_808.0) as f32
+    }
+}
+
+impl ToF32 for f32 {
+    fn to_f32(self) -> f32 {
+        self
+    }
+}
+
+impl ToF32 for f64 {
+    fn to_f32(self) -> f32 {
+        self as f32
+    }
+}
+
+/// Convert interleaved samples to f32 mono by averaging the channels
+///
+/// Doesn't allocate, so it can be used on the real-time audio thread.
+pub fn to_mono<T: ToF32>(data: &[T], channels: usize) -> impl Iterator<Item = f32> + '_ {
+    let channels = channels.max(1);
+    data.chunks(channels).map(move |frame| {
+        let sum: f32 = frame.iter().map(|&sample| sample.to_f32()).sum();
+        sum / channels as f32
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!((actual - expected).abs() < 1e-6, "expected {}, got {}", expected, actual);
+    }
+
+    #[test]
+    fn test_signed_integer_conversion() {
+        assert_close(i8::MIN.to_f32(), -1.0);
+        assert_close(0i8.to_f32(), 0.0);
+        assert_close(64i8.to_f32(), 0.5);
+
+        assert_close(i16::MIN.to_f32(), -1.0);
+        assert_close(0i16.to_f32(), 0.0);
+        assert_close(16_384i16.to_f32(), 0.5);
+        assert_close(i16::MAX.to_f32(), 32_767.0 / 32_768.0);
+
+        assert_close(i32::MIN.to_f32(), -1.0);
+        assert_close(0i32.to_f32(), 0.0);
+        assert_close((1i32 << 30).to_f32(), 0.5);
+
+        assert_close(i64::MIN.to_f32(), -1.0);
+        assert_close(0i64.to_f32(), 0.0);
+        assert_close((1i64 << 62).to_f32(), 0.5);
+    }
+
+    #[test]
+    fn test_unsigned_integer_conversion() {
+        assert_close(u8::MIN.to_f32(), -1.0);
+        assert_close(128u8.to_f32(), 0.0);
+        assert_close(192u8.to_f32(), 0.5);
+
+        assert_close(u16::MIN.to_f32(), -1.0);
+        assert_close(32_768u16.to_f32(), 0.0);
+        assert_close(49_152u16.to_f32(), 0.5);
+
+        assert_close(u32::MIN.to_f32(), -1.0);
+        assert_close((1u32 << 31).to_f32(), 0.0);
+        assert_close((3u32 << 30).to_f32(), 0.5);
+
+        assert_close(u64::MIN.to_f32(), -1.0);
+        assert_close((1u64 << 63).to_f32(), 0.0);
+        assert_close((3u64 << 62).to_f32(), 0.5);
+    }
+
+    #[test]
+    fn test_float_conversion() {
+        assert_close(0.25f32.to_f32(), 0.25);
+        assert_close((-1.0f32).to_f32(), -1.0);
+        assert_close(0.25f64.to_f32(), 0.25);
+        assert_close((-1.0f64).to_f32(), -1.0);
+    }
+
+    #[test]
+    fn test_to_mono_passes_through_single_channel() {
+        let out: Vec<f32> = to_mono(&[16_384i16, -16_384], 1).collect();
+        assert_eq!(out, vec![0.5, -0.5]);
+    }
+
+    #[test]
+    fn test_to_mono_averages_channels() {
+        // Two stereo frames of u16: (0.5, 0.0) and (-1.0, 0.0)
+        let out: Vec<f32> = to_mono(&[49_152u16, 32_768, 0, 32_768], 2).collect();
+        assert_eq!(out.len(), 2);
+        assert_close(out[0], 0.25);
+        assert_close(out[1], -0.5);
+    }
+}
@@ -81,6 +81,46 @@ pub fn compute_append(old_text: &str, new_text: &str) -> Option<String> {
     }
 }
 
+/// Maximum number of words compared by `strip_overlap`
+const MAX_OVERLAP_WORDS: usize = 8;
+
+/// Remove words at the start of `next` that repeat the end of `previous`
+///
+/// Consecutive transcriptions of overlapping audio (a long utterance split in two, or a
+/// sliding live preview window) can both contain the words around the boundary. The
+/// longest run of up to `MAX_OVERLAP_WORDS` words is removed, comparing words without
+/// case or punctuation.
+///
+/// # Examples
+///
+/// ```
+/// use live_transcribe::text_diff::strip_overlap;
+///
+/// assert_eq!(strip_overlap("we went to the", "To the park."), "park.");
+/// assert_eq!(strip_overlap("hello", "world"), "world");
+/// ```
+pub fn strip_overlap(previous: &str, next: &str) -> String {
+    fn normalize(word: &str) -> String {
+        word.chars()
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .collect()
+    }
+
+    let previous_words: Vec<String> = previous.split_whitespace().map(normalize).collect();
+    let next_words: Vec<&str> = next.split_whitespace().collect();
+    let max_overlap = MAX_OVERLAP_WORDS.min(previous_words.len()).min(next_words.len());
+
+    for overlap in (1..=max_overlap).rev() {
+        let previous_tail = &previous_words[previous_words.len() - overlap..];
+        if next_words[..overlap].iter().map(|word| normalize(word)).eq(previous_tail.iter().cloned()) {
+            return next_words[overlap..].join(" ");
+        }
+    }
+
+    next.to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -188,4 +228,17 @@ mod tests {
         let suffix = compute_append("", "Hello");
         assert_eq!(suffix, Some("Hello".to_string()));
     }
+
+    #[test]
+    fn test_strip_overlap_removes_longest_repeat() {
+        assert_eq!(strip_overlap("one two three ", "two three four"), "four");
+        assert_eq!(strip_overlap("I said no, no", "No, no way"), "way");
+    }
+
+    #[test]
+    fn test_strip_overlap_keeps_text_without_repeat() {
+        assert_eq!(strip_overlap("one two", "three four"), "three four");
+        assert_eq!(strip_overlap("", "three four"), "three four");
+        assert_eq!(strip_overlap("one two", "two"), "");
+    }
 }
@@ -1,5 +1,6 @@
 use anyhow::{Context, Result};
 use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters, WhisperState};
+use std::ffi::c_void;
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 use crate::config::TranscriptionConfig;
@@ -116,6 +117,21 @@ impl Transcriber {
 
 impl TranscriberWithState {
     pub fn new(config: TranscriptionConfig) -> Result<Self> {
+        let ctx = Self::load_context(&config)?;
+
+        // Create state once (loads CoreML model once)
+        let state = ctx.create_state()
+            .context("Failed to create Whisper state")?;
+
+        Ok(TranscriberWithState {
+            ctx,
+            state,
+            config,
+        })
+    }
+
+    /// Load the Whisper model for a configuration, to be shared between transcribers
+    pub fn load_context(config: &TranscriptionConfig) -> Result<Arc<WhisperContext>> {
         // Get model path
         let model_path = Transcriber::get_model_path(&config.model)?;
 
@@ -133,15 +149,7 @@ impl TranscriberWithState {
 
         println!("Whisper model loaded successfully (GPU: {})", config.use_gpu);
 
-        // Create state once (loads CoreML model once)
-        let state = ctx.create_state()
-            .context("Failed to create Whisper state")?;
-
-        Ok(TranscriberWithState {
-            ctx: Arc::new(ctx),
-            state,
-            config,
-        })
+        Ok(Arc::new(ctx))
     }
 
     /// Create a new transcriber with state using a shared context
@@ -159,6 +167,23 @@ impl TranscriberWithState {
     }
 
     pub fn transcribe(&mut self, audio_data: &[f32]) -> Result<String> {
+        let text = self.transcribe_with_abort(audio_data, &mut || false)?;
+        Ok(text.unwrap_or_default())
+    }
+
+    /// Transcribe audio, stopping early when `should_abort` returns true
+    ///
+    /// Whisper polls `should_abort` while it encodes and decodes, so a superseded request
+    /// stops within a fraction of its normal run time. Returns `None` if it was aborted.
+    pub fn transcribe_with_abort(
+        &mut self,
+        audio_data: &[f32],
+        should_abort: &mut dyn FnMut() -> bool,
+    ) -> Result<Option<String>> {
+        if should_abort() {
+            return Ok(None);
+        }
+
         // Create parameters for transcription
         let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
 
@@ -188,9 +213,23 @@ impl TranscriberWithState {
         // Default is -1.0, using 0.0 to only accept confident predictions
         params.set_logprob_thold(0.0);
 
+        // Let whisper check for cancellation between compute steps.
+        // Safety: `should_abort` outlives the `full` call below, which is the only place
+        // whisper calls the callback, and the trampoline only forwards to it.
+        let mut should_abort = should_abort;
+        unsafe {
+            params.set_abort_callback(Some(abort_trampoline));
+            params.set_abort_callback_user_data(&mut should_abort as *mut &mut dyn FnMut() -> bool as *mut c_void);
+        }
+
         // Reuse the existing state
-        self.state.full(params, audio_data)
-            .context("Failed to run Whisper transcription")?;
+        let outcome = self.state.full(params, audio_data);
+
+        // An aborted run reports a generic encode/decode failure
+        if should_abort() {
+            return Ok(None);
+        }
+        outcome.context("Failed to run Whisper transcription")?;
 
         // Get the number of segments
         let num_segments = self.state.full_n_segments()
@@ -208,7 +247,12 @@ impl TranscriberWithState {
 
         let final_result = result.trim().to_string();
         println!("  ✅ Whisper final result ({} segments): {:?}", num_segments, final_result);
-        Ok(final_result)
+        Ok(Some(final_result))
     }
 }
 
+/// Abort callback passed to whisper; `user_data` points to a `&mut dyn FnMut() -> bool`
+unsafe extern "C" fn abort_trampoline(user_data: *mut c_void) -> bool {
+    let should_abort = &mut *(user_data as *mut &mut dyn FnMut() -> bool);
+    should_abort()
+}
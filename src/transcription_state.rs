@@ -3,9 +3,14 @@
 /// This module encapsulates all the complex state management logic that was previously
 /// embedded in the main event loop, making it testable and maintainable.
 
-use crate::constants::{audio::MIN_WHISPER_SAMPLES, vad};
-use crate::text_diff::{compute_append, compute_text_diff};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+use crate::constants::{retry, streaming, vad};
+use crate::text_diff::{compute_append, compute_text_diff, strip_overlap};
 use crate::audio::AudioCapture;
+use crate::commit_policy::{reconcile, CommitPolicy, LocalAgreement};
+use crate::config::TranscriptionConfig;
+use crate::vad::{build_detector, quietest_point, RmsVad, VoiceActivityDetector};
 
 /// Actions that should be performed in response to state changes
 #[derive(Debug, Clone, PartialEq)]
@@ -25,19 +30,100 @@ pub enum Action {
         request_id: u64,
     },
 
+    /// Resubmit an utterance whose transcription failed
+    RetryVadRequest {
+        audio: Vec<f32>,
+        request_id: u64,
+        /// Use the configured fallback model for this attempt
+        use_fallback: bool,
+    },
+
     /// Submit live preview transcription request
     SubmitLiveRequest {
         audio: Vec<f32>,
         request_id: u64,
     },
 
+    /// An utterance could not be transcribed and its text is missing
+    UtteranceDropped {
+        sequence: u64,
+        duration_secs: f32,
+    },
+
     /// Cancel pending live preview request (VAD supersedes it)
-    CancelLiveRequest,
+    CancelLiveRequest {
+        request_id: u64,
+    },
+
+    /// Cancel every request with an ID below `before` (the session was discarded)
+    CancelAllRequests {
+        before: u64,
+    },
 
     /// No action needed
     NoAction,
 }
 
+/// A committed utterance waiting for its VAD transcription
+#[derive(Debug)]
+struct PendingCommit {
+    /// Position of the utterance in the session
+    sequence: u64,
+
+    request_id: u64,
+
+    /// Transcribed text, held here if it arrives before earlier utterances are done
+    text: Option<String>,
+
+    /// Audio of the utterance, kept until it is transcribed so a failure can be retried
+    audio: Vec<f32>,
+
+    /// Transcription attempts submitted so far
+    attempts: u32,
+
+    /// When to resubmit after a failed attempt
+    retry_at: Option<Instant>,
+
+    /// Continues an utterance that was split; its first words may repeat the previous text
+    continues_previous: bool,
+
+    /// Words already typed for this utterance under the local agreement policy
+    agreed_words: Vec<String>,
+}
+
+/// Streaming timing from the configuration, converted from milliseconds to chunks and samples
+#[derive(Debug, Clone, Copy)]
+pub struct StreamingTiming {
+    /// Consecutive silence chunks that commit an utterance
+    pub commit_silence_chunks: usize,
+
+    /// Trailing silence chunks kept in the VAD buffer
+    pub max_trailing_silence_chunks: usize,
+
+    /// Speech chunks in an utterance before the first live preview
+    pub live_preview_delay_chunks: usize,
+
+    /// Audio shorter than this is padded with silence for Whisper
+    pub min_whisper_samples: usize,
+}
+
+impl StreamingTiming {
+    pub fn from_config(config: &TranscriptionConfig) -> Self {
+        Self {
+            commit_silence_chunks: config.chunks(config.commit_silence_ms),
+            max_trailing_silence_chunks: config.chunks(config.trailing_silence_ms),
+            live_preview_delay_chunks: config.chunks(config.live_preview_delay_ms),
+            min_whisper_samples: (config.min_audio_ms * 16) as usize,
+        }
+    }
+}
+
+impl Default for StreamingTiming {
+    fn default() -> Self {
+        Self::from_config(&TranscriptionConfig::default())
+    }
+}
+
 /// Core transcription state machine
 pub struct TranscriptionState {
     /// VAD buffer: accumulates complete utterances
@@ -49,14 +135,32 @@ pub struct TranscriptionState {
     /// Live preview text: what's currently displayed on screen
     live_preview_text: String,
 
+    /// Start of the live preview window in the VAD buffer
+    live_window_start: usize,
+
+    /// Preview text for the audio before the live window, kept on screen
+    live_frozen_text: String,
+
+    /// Latest preview of the current window and the VAD buffer length it covers
+    last_live_result: Option<(String, usize)>,
+
+    /// VAD buffer length when the pending live request was submitted
+    pending_live_end: usize,
+
+    /// The VAD buffer continues an utterance that was split at the maximum length
+    split_continuation: bool,
+
     /// Number of consecutive silence chunks detected
     silence_streak: usize,
 
     /// Number of chunks processed since last VAD commit
     chunks_since_vad_commit: usize,
 
-    /// ID of pending VAD transcription request
-    pending_vad_request: Option<u64>,
+    /// Committed utterances awaiting transcription, in the order they were spoken
+    pending_vad_requests: VecDeque<PendingCommit>,
+
+    /// Sequence number of the next committed utterance
+    next_sequence: u64,
 
     /// ID of pending live preview transcription request
     pending_live_request: Option<u64>,
@@ -64,23 +168,77 @@ pub struct TranscriptionState {
     /// Counter for generating unique request IDs
     next_request_id: u64,
 
-    /// Silence detection threshold
+    /// Silence detection threshold (used for whole recordings in non-streaming mode)
     silence_threshold: f32,
+
+    /// Decides whether each chunk contains speech
+    detector: Box<dyn VoiceActivityDetector>,
+
+    /// Silence, preview and padding durations in chunks and samples
+    timing: StreamingTiming,
+
+    /// Audio captured just before the session started, prepended to the first utterance
+    /// if the session starts with speech
+    pre_roll: Vec<f32>,
+
+    /// A fallback model is configured for the last retry of a failing utterance
+    has_fallback_model: bool,
+
+    /// Confirms live preview words under the local agreement commit policy
+    /// (`None` with the silence policy, where live previews are provisional)
+    agreement: Option<LocalAgreement>,
 }
 
 impl TranscriptionState {
-    /// Create a new transcription state machine
+    /// Create a new transcription state machine using RMS silence detection
     pub fn new(silence_threshold: f32) -> Self {
+        Self::with_detector(silence_threshold, Box::new(RmsVad::new(silence_threshold)))
+    }
+
+    /// Create a state machine with the voice activity detector selected in the configuration
+    pub fn from_config(config: &TranscriptionConfig) -> Self {
+        let mut state = Self::with_detector(config.silence_threshold, build_detector(config));
+        state.timing = StreamingTiming::from_config(config);
+        state.has_fallback_model = config.fallback_model.is_some();
+        if config.commit_policy == CommitPolicy::LocalAgreement {
+            state.agreement = Some(LocalAgreement::new(config.agreement_count));
+        }
+        state
+    }
+
+    /// Switch to other settings (e.g. a profile's) before the next session
+    ///
+    /// Request IDs keep counting up, so late results from an earlier session can't be
+    /// mistaken for new ones, and the worker's cancellation watermarks still apply.
+    pub fn reconfigure(&mut self, config: &TranscriptionConfig) {
+        let next_request_id = self.next_request_id;
+        *self = Self::from_config(config);
+        self.next_request_id = next_request_id;
+    }
+
+    /// Create a state machine with a specific voice activity detector
+    pub fn with_detector(silence_threshold: f32, detector: Box<dyn VoiceActivityDetector>) -> Self {
         Self {
             vad_buffer: Vec::new(),
             vad_committed_text: String::new(),
             live_preview_text: String::new(),
+            live_window_start: 0,
+            live_frozen_text: String::new(),
+            last_live_result: None,
+            pending_live_end: 0,
+            split_continuation: false,
             silence_streak: 0,
             chunks_since_vad_commit: 0,
-            pending_vad_request: None,
+            pending_vad_requests: VecDeque::new(),
+            next_sequence: 1,
             pending_live_request: None,
             next_request_id: 1,
             silence_threshold,
+            detector,
+            timing: StreamingTiming::default(),
+            pre_roll: Vec::new(),
+            has_fallback_model: false,
+            agreement: None,
         }
     }
 
@@ -89,74 +247,82 @@ impl TranscriptionState {
         self.vad_buffer.clear();
         self.vad_committed_text.clear();
         self.live_preview_text.clear();
+        self.reset_live_window();
+        self.split_continuation = false;
+        if let Some(agreement) = &mut self.agreement {
+            agreement.reset();
+        }
         self.silence_streak = 0;
         self.chunks_since_vad_commit = 0;
-        self.pending_vad_request = None;
+        self.pending_vad_requests.clear();
+        self.next_sequence = 1;
         self.pending_live_request = None;
+        self.pre_roll.clear();
+        self.detector.reset();
+    }
+
+    /// Discard the session: erase everything it typed and forget its pending transcriptions
+    ///
+    /// Returns the edit that deletes the session's text from the screen (if any), followed
+    /// by the cancellation of every request submitted so far. Results that still arrive
+    /// for those requests are ignored.
+    pub fn cancel(&mut self) -> Vec<Action> {
+        let mut actions = Vec::new();
+
+        let chars_to_delete = self.live_preview_text.chars().count();
+        if chars_to_delete > 0 {
+            actions.push(Action::ReplaceText {
+                chars_to_delete,
+                new_text: String::new(),
+            });
+        }
+        actions.push(Action::CancelAllRequests { before: self.next_request_id });
+
+        self.reset();
+        actions
+    }
+
+    /// Set the audio captured just before recording started or resumed (always-on capture)
+    ///
+    /// The first syllable is often spoken as the hotkey is pressed; the pre-roll keeps it
+    /// when the first chunk contains speech.
+    pub fn set_pre_roll(&mut self, audio: Vec<f32>) {
+        self.pre_roll = audio;
+    }
+
+    /// Silence threshold currently applied to each chunk
+    ///
+    /// With adaptive calibration this follows the estimated noise floor of the microphone.
+    pub fn effective_threshold(&self) -> f32 {
+        self.detector.threshold().unwrap_or(self.silence_threshold)
     }
 
     /// Process a new audio chunk and return actions to perform
     pub fn process_audio_chunk(&mut self, new_audio: &[f32]) -> Vec<Action> {
         let mut actions = Vec::new();
 
-        let is_silence = AudioCapture::is_silence(new_audio, self.silence_threshold);
+        let is_silence = !self.detector.is_speech(new_audio);
+
+        // The pre-roll only belongs to an utterance that starts with the session
+        let pre_roll = std::mem::take(&mut self.pre_roll);
 
         if is_silence {
             self.silence_streak += 1;
-            println!("🔇 Silence chunk {}", self.silence_streak);
+            println!("🔇 Silence chunk {} (threshold: {:.4})", self.silence_streak, self.effective_threshold());
 
             // Only send LIMITED trailing silence to Whisper
             // Too much trailing silence causes hallucinations
-            if !self.vad_buffer.is_empty() && self.silence_streak <= vad::MAX_TRAILING_SILENCE_CHUNKS {
+            if !self.vad_buffer.is_empty() && self.silence_streak <= self.timing.max_trailing_silence_chunks {
                 self.vad_buffer.extend_from_slice(new_audio);
                 println!("   ➕ Added trailing silence chunk {} to VAD buffer", self.silence_streak);
             }
 
             // After sufficient silence, commit VAD transcription
-            if self.silence_streak >= vad::COMMIT_SILENCE_CHUNKS
-                && !self.vad_buffer.is_empty()
-                && self.pending_vad_request.is_none()
-            {
-                let buffer_duration = self.vad_buffer.len() as f32 / 16000.0;
-                println!("💾 VAD: Silence detected - transcribing {:.1}s of speech + trailing silence", buffer_duration);
-
-                // Debug: calculate RMS of VAD buffer
-                let vad_rms = if !self.vad_buffer.is_empty() {
-                    let sum_squares: f32 = self.vad_buffer.iter().map(|&x| x * x).sum();
-                    (sum_squares / self.vad_buffer.len() as f32).sqrt()
-                } else {
-                    0.0
-                };
-                println!("   VAD buffer RMS: {:.4}", vad_rms);
-
-                // Pad VAD buffer to minimum length for Whisper if needed
-                if self.vad_buffer.len() < MIN_WHISPER_SAMPLES {
-                    println!("   Padding VAD buffer from {:.1}s to {:.1}s",
-                             buffer_duration,
-                             MIN_WHISPER_SAMPLES as f32 / 16000.0);
-                    self.vad_buffer.resize(MIN_WHISPER_SAMPLES, 0.0);
-                }
-
-                // Generate request ID and submit VAD transcription
-                let request_id = self.generate_request_id();
-                println!("   Submitting VAD transcription request for {} samples", self.vad_buffer.len());
-
-                actions.push(Action::SubmitVadRequest {
-                    audio: self.vad_buffer.clone(),
-                    request_id,
-                });
-
-                self.pending_vad_request = Some(request_id);
-
-                // Cancel any pending live preview - VAD commit supersedes it
-                if self.pending_live_request.is_some() {
-                    actions.push(Action::CancelLiveRequest);
-                    self.pending_live_request = None;
-                }
-
-                // Reset for next utterance
-                self.vad_buffer.clear();
-                self.chunks_since_vad_commit = 0;
+            // (earlier utterances may still be in flight; results are applied in order)
+            if self.silence_streak >= self.timing.commit_silence_chunks && !self.vad_buffer.is_empty() {
+                println!("💾 VAD: Silence detected - transcribing {:.1}s of speech + trailing silence",
+                         self.vad_buffer.len() as f32 / 16000.0);
+                self.commit_vad_buffer(&mut actions);
             }
 
             return actions;
@@ -170,17 +336,28 @@ impl TranscriptionState {
         self.chunks_since_vad_commit += 1;
 
         // VAD: Accumulate speech audio
+        if self.vad_buffer.is_empty() && !pre_roll.is_empty() {
+            println!("⏪ Prepending {:.0}ms of pre-roll", pre_roll.len() as f32 / 16.0);
+            self.vad_buffer = pre_roll;
+        }
         self.vad_buffer.extend_from_slice(new_audio);
         println!("📼 VAD buffer: {:.1}s accumulated", self.vad_buffer.len() as f32 / 16000.0);
 
-        // LIVE PREVIEW: Transcribe VAD buffer for immediate feedback
-        if self.chunks_since_vad_commit >= crate::constants::streaming::LIVE_PREVIEW_DELAY_CHUNKS
+        // Don't let continuous speech outgrow Whisper's window
+        if self.vad_buffer.len() >= vad::MAX_UTTERANCE_SAMPLES {
+            self.split_vad_buffer(&mut actions);
+        }
+
+        // LIVE PREVIEW: Transcribe the tail of the VAD buffer for immediate feedback
+        if self.chunks_since_vad_commit >= self.timing.live_preview_delay_chunks
             && self.pending_live_request.is_none()
         {
-            // Pad VAD buffer for transcription if needed
-            let mut preview_buffer = self.vad_buffer.clone();
-            if preview_buffer.len() < MIN_WHISPER_SAMPLES {
-                preview_buffer.resize(MIN_WHISPER_SAMPLES, 0.0);
+            self.advance_live_window();
+
+            // Pad preview window for transcription if needed
+            let mut preview_buffer = self.vad_buffer[self.live_window_start..].to_vec();
+            if preview_buffer.len() < self.timing.min_whisper_samples {
+                preview_buffer.resize(self.timing.min_whisper_samples, 0.0);
             }
 
             // Generate request ID and submit live preview transcription
@@ -191,40 +368,228 @@ impl TranscriptionState {
             });
 
             self.pending_live_request = Some(request_id);
-        } else if self.chunks_since_vad_commit < crate::constants::streaming::LIVE_PREVIEW_DELAY_CHUNKS {
+            self.pending_live_end = self.vad_buffer.len();
+        } else if self.chunks_since_vad_commit < self.timing.live_preview_delay_chunks {
             println!("⏳ Live preview: Waiting for more audio ({}/{} chunks)",
                      self.chunks_since_vad_commit,
-                     crate::constants::streaming::LIVE_PREVIEW_DELAY_CHUNKS);
+                     self.timing.live_preview_delay_chunks);
         }
 
         actions
     }
 
+    /// Submit the VAD buffer for transcription and start a new utterance
+    fn commit_vad_buffer(&mut self, actions: &mut Vec<Action>) {
+        let buffer_duration = self.vad_buffer.len() as f32 / 16000.0;
+
+        // Debug: calculate RMS of VAD buffer
+        println!("   VAD buffer RMS: {:.4}", crate::vad::rms(&self.vad_buffer));
+
+        // Pad VAD buffer to minimum length for Whisper if needed
+        if self.vad_buffer.len() < self.timing.min_whisper_samples {
+            println!("   Padding VAD buffer from {:.1}s to {:.1}s",
+                     buffer_duration,
+                     self.timing.min_whisper_samples as f32 / 16000.0);
+            self.vad_buffer.resize(self.timing.min_whisper_samples, 0.0);
+        }
+
+        // Generate request ID and submit VAD transcription
+        let request_id = self.generate_request_id();
+        println!("   Submitting VAD transcription request for {} samples", self.vad_buffer.len());
+
+        let audio = std::mem::take(&mut self.vad_buffer);
+        self.push_pending_commit(request_id, audio.clone());
+        actions.push(Action::SubmitVadRequest { audio, request_id });
+
+        // Cancel any pending live preview - VAD commit supersedes it
+        if let Some(request_id) = self.pending_live_request.take() {
+            actions.push(Action::CancelLiveRequest { request_id });
+        }
+
+        // Reset for next utterance
+        self.chunks_since_vad_commit = 0;
+        self.reset_live_window();
+    }
+
+    /// Commit the first part of an utterance that has reached the maximum length
+    ///
+    /// The cut is placed at the quietest point shortly before the limit. The audio just
+    /// before the cut stays at the start of the next part, so a word on the boundary isn't
+    /// lost; words transcribed twice are removed when the results are joined.
+    fn split_vad_buffer(&mut self, actions: &mut Vec<Action>) {
+        let search_end = self.vad_buffer.len().min(vad::MAX_UTTERANCE_SAMPLES);
+        let search_start = search_end.saturating_sub(vad::SPLIT_SEARCH_SAMPLES);
+        let cut = search_start
+            + quietest_point(&self.vad_buffer[search_start..search_end], vad::SPLIT_FRAME_SAMPLES);
+        let remainder = self.vad_buffer[cut.saturating_sub(vad::SPLIT_OVERLAP_SAMPLES)..].to_vec();
+
+        println!("✂️  VAD: No pause for {:.0}s - committing first {:.1}s",
+                 self.vad_buffer.len() as f32 / 16000.0, cut as f32 / 16000.0);
+        self.vad_buffer.truncate(cut);
+        self.commit_vad_buffer(actions);
+
+        self.vad_buffer = remainder;
+        self.split_continuation = true;
+    }
+
+    /// Slide the live preview window forward once it exceeds the maximum preview length
+    ///
+    /// The preview text for the audio before the new window is frozen on screen, so older
+    /// audio isn't re-transcribed for every preview.
+    fn advance_live_window(&mut self) {
+        if self.vad_buffer.len() - self.live_window_start <= streaming::LIVE_PREVIEW_MAX_SAMPLES {
+            return;
+        }
+        let Some((text, end)) = self.last_live_result.take() else {
+            return; // Nothing to freeze yet
+        };
+
+        self.live_frozen_text = self.join_live_text(&text);
+        self.live_window_start = end.saturating_sub(streaming::LIVE_PREVIEW_OVERLAP_SAMPLES);
+        println!("⏩ Live preview: window moved to {:.1}s", self.live_window_start as f32 / 16000.0);
+    }
+
+    /// Preview text for the whole utterance: frozen text followed by the window's text
+    fn join_live_text(&self, window_text: &str) -> String {
+        let window_text = strip_overlap(&self.live_frozen_text, window_text);
+        match (self.live_frozen_text.is_empty(), window_text.is_empty()) {
+            (true, _) => window_text,
+            (false, true) => self.live_frozen_text.clone(),
+            (false, false) => format!("{} {}", self.live_frozen_text, window_text),
+        }
+    }
+
+    fn reset_live_window(&mut self) {
+        self.live_window_start = 0;
+        self.live_frozen_text.clear();
+        self.last_live_result = None;
+    }
+
+    /// Flush the utterance in progress when recording stops (streaming mode)
+    ///
+    /// `remaining_audio` is whatever was captured after the last chunk. The VAD buffer is
+    /// committed without waiting for trailing silence, and the live preview is cancelled
+    /// since the commit supersedes it.
+    pub fn finish(&mut self, remaining_audio: &[f32]) -> Vec<Action> {
+        let mut actions = Vec::new();
+
+        if !remaining_audio.is_empty()
+            && (!self.vad_buffer.is_empty() || self.detector.is_speech(remaining_audio))
+        {
+            if self.vad_buffer.is_empty() {
+                self.vad_buffer.append(&mut self.pre_roll);
+            }
+            self.vad_buffer.extend_from_slice(remaining_audio);
+        }
+        self.pre_roll.clear();
+
+        if let Some(request_id) = self.pending_live_request.take() {
+            actions.push(Action::CancelLiveRequest { request_id });
+        }
+
+        if !self.vad_buffer.is_empty() {
+            println!("💾 VAD: Recording stopped - transcribing final {:.1}s", self.vad_buffer.len() as f32 / 16000.0);
+            self.commit_vad_buffer(&mut actions);
+        }
+
+        actions
+    }
+
+    /// Pause the session: flush the utterance in progress but keep the committed text
+    ///
+    /// Audio processed after resuming starts a new utterance that continues the session's
+    /// text and spacing, unlike a new session after `reset`.
+    pub fn pause(&mut self, remaining_audio: &[f32]) -> Vec<Action> {
+        let actions = self.finish(remaining_audio);
+        self.silence_streak = 0;
+        actions
+    }
+
+    /// Whether all speech from a finished session has been transcribed
+    pub fn is_finished(&self) -> bool {
+        self.vad_buffer.is_empty() && self.pending_vad_requests.is_empty()
+    }
+
+    /// Submit a complete recording for transcription (non-streaming mode)
+    ///
+    /// The result comes back as a regular VAD commit, so `process_vad_result` types it.
+    /// The pre-roll, if any, is included. Returns `None` if the recording contains no speech.
+    pub fn submit_recording(&mut self, recording: Vec<f32>) -> Option<Action> {
+        let mut audio = std::mem::take(&mut self.pre_roll);
+        audio.extend(recording);
+
+        if AudioCapture::is_silence(&audio, self.silence_threshold) {
+            println!("🔇 Recording contains no speech, nothing to transcribe");
+            return None;
+        }
+
+        // Pad recording to minimum length for Whisper if needed
+        if audio.len() < self.timing.min_whisper_samples {
+            audio.resize(self.timing.min_whisper_samples, 0.0);
+        }
+
+        let request_id = self.generate_request_id();
+        println!("📤 Submitting {:.1}s recording for transcription", audio.len() as f32 / 16000.0);
+        self.push_pending_commit(request_id, audio.clone());
+
+        Some(Action::SubmitVadRequest { audio, request_id })
+    }
+
     /// Process a VAD commit result and return keyboard action
+    ///
+    /// Results can arrive out of order when several utterances are in flight. A result is
+    /// held until every earlier utterance has been transcribed, then all ready results
+    /// are committed together.
     pub fn process_vad_result(&mut self, text: String, request_id: u64) -> Action {
-        // Verify this is the request we're waiting for
-        if self.pending_vad_request != Some(request_id) {
+        // Verify this is a request we're waiting for
+        let Some(commit) = self.pending_vad_requests.iter_mut().find(|c| c.request_id == request_id) else {
             return Action::NoAction;
+        };
+
+        println!("✅ VAD result for utterance {}: \"{}\"", commit.sequence, text);
+        commit.text = Some(text);
+        commit.audio = Vec::new();
+
+        self.apply_ready_commits()
+    }
+
+    /// Commit results from the front of the queue, stopping at the first one still in flight
+    fn apply_ready_commits(&mut self) -> Action {
+        // Build what the full committed text should be
+        let mut new_vad_committed = self.vad_committed_text.clone();
+        while self.pending_vad_requests.front().is_some_and(|c| c.text.is_some()) {
+            let commit = self.pending_vad_requests.pop_front().unwrap();
+            let mut text = commit.text.unwrap_or_default();
+            if !commit.agreed_words.is_empty() {
+                text = reconcile(&commit.agreed_words, &text);
+            }
+            if commit.continues_previous {
+                text = strip_overlap(&new_vad_committed, &text);
+            }
+            if !text.is_empty() {
+                new_vad_committed = new_vad_committed + &text + " ";
+            }
         }
 
-        self.pending_vad_request = None;
-        println!("✅ VAD committed: \"{}\"", text);
+        if let Some(waiting) = self.pending_vad_requests.front() {
+            println!("   ⏳ Waiting for utterance {} ({} in flight)", waiting.sequence, self.pending_vad_requests.len());
+        }
 
-        if text.is_empty() {
+        if new_vad_committed == self.vad_committed_text {
             return Action::NoAction;
         }
 
-        // Build what the full committed text should be
-        let new_vad_committed = self.vad_committed_text.clone() + &text + " ";
+        // Words typed under local agreement for later utterances stay on screen
+        let screen_text = new_vad_committed.clone() + &self.agreed_text();
 
         // Determine keyboard action based on relationship between new VAD and current screen text
-        let action = if let Some(suffix) = compute_append(&self.live_preview_text, &new_vad_committed) {
+        let action = if let Some(suffix) = compute_append(&self.live_preview_text, &screen_text) {
             // Just append the new part
             println!("➕ VAD appended: \"{}\"", suffix);
             Action::AppendText(suffix)
         } else if !self.live_preview_text.is_empty() {
             // VAD diverged from live preview - find common prefix to minimize flickering
-            let diff = compute_text_diff(&self.live_preview_text, &new_vad_committed);
+            let diff = compute_text_diff(&self.live_preview_text, &screen_text);
 
             if diff.chars_to_delete > 0 || !diff.suffix_to_type.is_empty() {
                 println!("🔄 VAD partial update: kept {} bytes, changed ending", diff.common_prefix_bytes);
@@ -237,13 +602,13 @@ impl TranscriptionState {
             }
         } else {
             // No live preview - just type the VAD result
-            println!("➕ VAD typed: \"{}\"", new_vad_committed.trim());
-            Action::AppendText(new_vad_committed.clone())
+            println!("➕ VAD typed: \"{}\"", screen_text.trim());
+            Action::AppendText(screen_text.clone())
         };
 
         // Update VAD committed state
-        self.vad_committed_text = new_vad_committed.clone();
-        self.live_preview_text = new_vad_committed;
+        self.vad_committed_text = new_vad_committed;
+        self.live_preview_text = screen_text;
 
         println!("   State: {} chars committed", self.vad_committed_text.chars().count());
 
@@ -265,8 +630,24 @@ impl TranscriptionState {
 
         println!("👁️  Live preview: \"{}\"", text);
 
-        // Build full text: VAD committed + new live preview
-        let full_live_text = self.vad_committed_text.clone() + &text;
+        // Build full text: VAD committed + frozen preview + new live preview. With local
+        // agreement, only the words consecutive previews agree on are shown.
+        let utterance_text = self.join_live_text(&text);
+        let full_live_text = match &mut self.agreement {
+            Some(agreement) => {
+                let confirmed = agreement.insert(&utterance_text);
+                if confirmed > 0 {
+                    println!("🤝 Local agreement: confirmed {} more words", confirmed);
+                }
+                self.vad_committed_text.clone() + &self.agreed_text()
+            }
+            None => self.vad_committed_text.clone() + &utterance_text,
+        };
+        self.last_live_result = Some((text, self.pending_live_end));
+
+        if full_live_text == self.live_preview_text {
+            return Action::NoAction;
+        }
 
         // Determine keyboard action
         let action = if let Some(suffix) = compute_append(&self.live_preview_text, &full_live_text) {
@@ -304,13 +685,116 @@ impl TranscriptionState {
     }
 
     /// Process a transcription error
-    pub fn process_error(&mut self, request_id: u64) {
-        if self.pending_vad_request == Some(request_id) {
-            self.pending_vad_request = None;
+    ///
+    /// A failed VAD commit is retried after a backoff (see `due_retries`). Once it has used
+    /// up its attempts it is dropped, which can release results queued behind it.
+    pub fn process_error(&mut self, request_id: u64) -> Vec<Action> {
+        let Some(commit) = self.pending_vad_requests.iter_mut().find(|c| c.request_id == request_id) else {
+            return vec![self.abandon_request(request_id)];
+        };
+
+        if commit.attempts < retry::MAX_ATTEMPTS {
+            let backoff = retry_backoff(commit.attempts);
+            println!("🔁 Utterance {} failed (attempt {}/{}), retrying in {}ms",
+                     commit.sequence, commit.attempts, retry::MAX_ATTEMPTS, backoff.as_millis());
+            commit.retry_at = Some(Instant::now() + backoff);
+            return Vec::new();
         }
+
+        eprintln!("❌ Utterance {} failed {} times, dropping it", commit.sequence, commit.attempts);
+        let dropped = Action::UtteranceDropped {
+            sequence: commit.sequence,
+            duration_secs: commit.audio.len() as f32 / 16000.0,
+        };
+        vec![dropped, self.abandon_request(request_id)]
+    }
+
+    /// Resubmit failed utterances whose backoff has elapsed
+    ///
+    /// Each retry gets a new request ID, so a late result from an earlier attempt is ignored.
+    pub fn due_retries(&mut self, now: Instant) -> Vec<Action> {
+        let mut actions = Vec::new();
+        for index in 0..self.pending_vad_requests.len() {
+            let due = matches!(self.pending_vad_requests[index].retry_at, Some(at) if at <= now);
+            if !due {
+                continue;
+            }
+
+            let request_id = self.generate_request_id();
+            let commit = &mut self.pending_vad_requests[index];
+            commit.retry_at = None;
+            commit.attempts += 1;
+            commit.request_id = request_id;
+
+            // The last attempt uses the fallback model, if there is one
+            let use_fallback = self.has_fallback_model && commit.attempts == retry::MAX_ATTEMPTS;
+            println!("🔁 Retrying utterance {} (attempt {}/{}{})",
+                     commit.sequence, commit.attempts, retry::MAX_ATTEMPTS,
+                     if use_fallback { ", fallback model" } else { "" });
+
+            actions.push(Action::RetryVadRequest {
+                audio: commit.audio.clone(),
+                request_id,
+                use_fallback,
+            });
+        }
+        actions
+    }
+
+    /// Process a request the worker cancelled instead of transcribing
+    pub fn process_cancelled(&mut self, request_id: u64) -> Action {
+        self.abandon_request(request_id)
+    }
+
+    /// Stop waiting for a request that won't produce text
+    fn abandon_request(&mut self, request_id: u64) -> Action {
         if self.pending_live_request == Some(request_id) {
             self.pending_live_request = None;
         }
+
+        match self.pending_vad_requests.iter_mut().find(|c| c.request_id == request_id) {
+            Some(commit) => {
+                println!("⏭️  Skipping utterance {} (no transcription)", commit.sequence);
+                commit.text = Some(String::new());
+                self.apply_ready_commits()
+            }
+            None => Action::NoAction,
+        }
+    }
+
+    /// Text committed by VAD transcriptions so far in this session
+    pub fn committed_text(&self) -> &str {
+        &self.vad_committed_text
+    }
+
+    /// Words typed under the local agreement policy that aren't part of the committed text yet
+    ///
+    /// Covers utterances waiting for their VAD transcription, then the utterance in progress.
+    fn agreed_text(&self) -> String {
+        let mut text: String = self.pending_vad_requests
+            .iter()
+            .filter(|c| !c.agreed_words.is_empty())
+            .map(|c| c.agreed_words.join(" ") + " ")
+            .collect();
+        if let Some(agreement) = &self.agreement {
+            text.push_str(&agreement.confirmed_text());
+        }
+        text
+    }
+
+    /// Queue a committed utterance behind the ones already in flight
+    fn push_pending_commit(&mut self, request_id: u64, audio: Vec<f32>) {
+        self.pending_vad_requests.push_back(PendingCommit {
+            sequence: self.next_sequence,
+            request_id,
+            text: None,
+            audio,
+            attempts: 1,
+            retry_at: None,
+            continues_previous: std::mem::take(&mut self.split_continuation),
+            agreed_words: self.agreement.as_mut().map(LocalAgreement::take_confirmed).unwrap_or_default(),
+        });
+        self.next_sequence += 1;
     }
 
     /// Generate a unique request ID
@@ -322,6 +806,12 @@ impl TranscriptionState {
     }
 }
 
+/// Delay before retrying an utterance that has failed `attempts` times
+fn retry_backoff(attempts: u32) -> Duration {
+    let factor = 1u64 << attempts.saturating_sub(1).min(16);
+    Duration::from_millis((retry::INITIAL_BACKOFF_MS * factor).min(retry::MAX_BACKOFF_MS))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -357,7 +847,7 @@ mod tests {
         let mut state = TranscriptionState::new(0.01);
         state.live_preview_text = "Hello".to_string();
         state.vad_committed_text = String::new();
-        state.pending_vad_request = Some(1); // Mark as pending
+        state.push_pending_commit(1, Vec::new()); // Mark as pending
 
         let action = state.process_vad_result("Hello world".to_string(), 1);
 
@@ -377,7 +867,7 @@ mod tests {
         let mut state = TranscriptionState::new(0.01);
         state.live_preview_text = "Hello world".to_string();
         state.vad_committed_text = String::new();
-        state.pending_vad_request = Some(1); // Mark as pending
+        state.push_pending_commit(1, Vec::new()); // Mark as pending
 
         let action = state.process_vad_result("Hello there".to_string(), 1);
 
@@ -409,14 +899,487 @@ mod tests {
         assert_eq!(state.live_preview_text, "Hello world");
     }
 
+    #[test]
+    fn test_submit_recording_types_result() {
+        let mut state = TranscriptionState::new(0.01);
+
+        let request_id = match state.submit_recording(create_speech_audio(500)) {
+            Some(Action::SubmitVadRequest { audio, request_id }) => {
+                assert_eq!(audio.len(), StreamingTiming::default().min_whisper_samples); // Padded for Whisper
+                request_id
+            }
+            other => panic!("Expected SubmitVadRequest action, got {:?}", other),
+        };
+
+        let action = state.process_vad_result("Hello world".to_string(), request_id);
+        assert_eq!(action, Action::AppendText("Hello world ".to_string()));
+    }
+
+    #[test]
+    fn test_submit_recording_skips_silence() {
+        let mut state = TranscriptionState::new(0.01);
+        assert_eq!(state.submit_recording(create_silence_audio(2000)), None);
+    }
+
+    #[test]
+    fn test_finish_commits_remaining_speech() {
+        let mut state = TranscriptionState::new(0.01);
+        for _ in 0..StreamingTiming::default().live_preview_delay_chunks {
+            state.process_audio_chunk(&create_speech_audio(300));
+        }
+        assert!(state.pending_live_request.is_some());
+
+        // Stop mid-utterance, with a bit of audio after the last chunk
+        let actions = state.finish(&create_speech_audio(100));
+
+        assert!(matches!(actions[0], Action::CancelLiveRequest { .. }));
+        let Action::SubmitVadRequest { audio, request_id } = &actions[1] else {
+            panic!("expected a VAD commit, got {:?}", actions);
+        };
+        assert_eq!(audio.len(), (StreamingTiming::default().live_preview_delay_chunks * 300 + 100) * 16);
+        assert!(!state.is_finished());
+
+        state.process_vad_result("last sentence".to_string(), *request_id);
+        assert!(state.is_finished());
+        assert_eq!(state.committed_text(), "last sentence ");
+    }
+
+    #[test]
+    fn test_finish_commits_while_earlier_commit_in_flight() {
+        let mut state = TranscriptionState::new(0.01);
+        state.push_pending_commit(7, Vec::new());
+        state.vad_buffer = create_speech_audio(500);
+
+        let actions = state.finish(&[]);
+        let [Action::SubmitVadRequest { request_id, .. }] = actions.as_slice() else {
+            panic!("expected a VAD commit, got {:?}", actions);
+        };
+        let request_id = *request_id;
+
+        // The final utterance is held until the earlier one comes back
+        assert_eq!(state.process_vad_result("second".to_string(), request_id), Action::NoAction);
+        assert!(!state.is_finished());
+
+        assert_eq!(
+            state.process_vad_result("first".to_string(), 7),
+            Action::AppendText("first second ".to_string())
+        );
+        assert!(state.is_finished());
+    }
+
+    #[test]
+    fn test_next_utterance_commits_while_previous_in_flight() {
+        let mut state = TranscriptionState::new(0.01);
+        let mut commits = 0;
+
+        for _ in 0..2 {
+            for _ in 0..3 {
+                state.process_audio_chunk(&create_speech_audio(300));
+            }
+            for _ in 0..StreamingTiming::default().commit_silence_chunks {
+                let actions = state.process_audio_chunk(&create_silence_audio(300));
+                commits += actions.iter().filter(|a| matches!(a, Action::SubmitVadRequest { .. })).count();
+            }
+        }
+
+        // The second utterance gets its own commit instead of merging into the first
+        assert_eq!(commits, 2);
+        assert_eq!(state.pending_vad_requests.len(), 2);
+    }
+
+    /// Commit one utterance per word, deliver results in `order`, and return the typed text
+    fn commit_and_deliver(words: &[&str], order: &[usize]) -> String {
+        let mut state = TranscriptionState::new(0.01);
+        let request_ids: Vec<u64> = words
+            .iter()
+            .map(|_| {
+                state.vad_buffer = create_speech_audio(300);
+                let mut actions = Vec::new();
+                state.commit_vad_buffer(&mut actions);
+                match actions.as_slice() {
+                    [Action::SubmitVadRequest { request_id, .. }] => *request_id,
+                    other => panic!("expected a VAD commit, got {:?}", other),
+                }
+            })
+            .collect();
+
+        let mut typed = String::new();
+        let mut delivered = Vec::new();
+        for &index in order {
+            match state.process_vad_result(words[index].to_string(), request_ids[index]) {
+                Action::AppendText(text) => typed.push_str(&text),
+                Action::NoAction => {}
+                other => panic!("unexpected action {:?}", other),
+            }
+            delivered.push(index);
+
+            // Only the utterances up to the first one still in flight are committed
+            let prefix = (0..words.len()).take_while(|i| delivered.contains(i)).count();
+            let expected: String = words[..prefix].iter().map(|w| format!("{} ", w)).collect();
+            assert_eq!(state.committed_text(), expected, "after delivering {:?}", delivered);
+        }
+
+        assert!(state.is_finished());
+        typed
+    }
+
+    #[test]
+    fn test_shuffled_vad_results_commit_in_order() {
+        let words = ["one", "two", "three", "four", "five"];
+        let orders: [[usize; 5]; 6] = [
+            [0, 1, 2, 3, 4],
+            [4, 3, 2, 1, 0],
+            [2, 0, 4, 1, 3],
+            [1, 0, 3, 2, 4],
+            [3, 4, 0, 2, 1],
+            [0, 4, 1, 3, 2],
+        ];
+
+        for order in &orders {
+            assert_eq!(commit_and_deliver(&words, order), "one two three four five ", "order {:?}", order);
+        }
+    }
+
+    #[test]
+    fn test_failed_commit_is_retried_with_its_audio() {
+        let mut state = TranscriptionState::new(0.01);
+        let first_id = state.generate_request_id();
+        state.push_pending_commit(first_id, create_speech_audio(500));
+
+        assert!(state.process_error(first_id).is_empty());
+        assert!(state.due_retries(Instant::now()).is_empty()); // Still backing off
+
+        let actions = state.due_retries(Instant::now() + Duration::from_secs(60));
+        let [Action::RetryVadRequest { audio, request_id, use_fallback: false }] = actions.as_slice() else {
+            panic!("expected a retry, got {:?}", actions);
+        };
+        assert_eq!(audio.len(), 500 * 16);
+        assert_ne!(*request_id, first_id);
+
+        // A late result from the failed attempt is ignored; the retry's result is typed
+        assert_eq!(state.process_vad_result("stale".to_string(), first_id), Action::NoAction);
+        assert_eq!(
+            state.process_vad_result("recovered".to_string(), *request_id),
+            Action::AppendText("recovered ".to_string())
+        );
+        assert!(state.is_finished());
+    }
+
+    #[test]
+    fn test_commit_dropped_after_max_attempts() {
+        let config = TranscriptionConfig {
+            fallback_model: Some("tiny.en".to_string()),
+            ..TranscriptionConfig::default()
+        };
+        let mut state = TranscriptionState::from_config(&config);
+        state.push_pending_commit(1, create_speech_audio(2000));
+        state.push_pending_commit(2, Vec::new());
+        assert_eq!(state.process_vad_result("after".to_string(), 2), Action::NoAction);
+
+        let mut request_id = 1;
+        let mut fallback_attempts = 0;
+        for _ in 1..retry::MAX_ATTEMPTS {
+            assert!(state.process_error(request_id).is_empty());
+            match state.due_retries(Instant::now() + Duration::from_secs(60)).as_slice() {
+                [Action::RetryVadRequest { request_id: id, use_fallback, .. }] => {
+                    request_id = *id;
+                    fallback_attempts += *use_fallback as u32;
+                }
+                other => panic!("expected a retry, got {:?}", other),
+            }
+        }
+        assert_eq!(fallback_attempts, 1); // Only the last attempt
+
+        // Giving up reports the dropped utterance and releases the one queued behind it
+        let actions = state.process_error(request_id);
+        assert_eq!(actions, vec![
+            Action::UtteranceDropped { sequence: 1, duration_secs: 2.0 },
+            Action::AppendText("after ".to_string()),
+        ]);
+        assert!(state.is_finished());
+    }
+
+    #[test]
+    fn test_retry_backoff_is_bounded() {
+        assert_eq!(retry_backoff(1), Duration::from_millis(retry::INITIAL_BACKOFF_MS));
+        assert_eq!(retry_backoff(2), Duration::from_millis(retry::INITIAL_BACKOFF_MS * 2));
+        assert_eq!(retry_backoff(40), Duration::from_millis(retry::MAX_BACKOFF_MS));
+    }
+
+    #[test]
+    fn test_long_utterance_split_at_quiet_point() {
+        let mut state = TranscriptionState::new(0.01);
+
+        // Continuous speech with a quieter stretch (still above the threshold) at 22s
+        let mut speech = create_speech_audio(27_000);
+        let dip = 22 * 16000;
+        speech[dip..dip + 640].iter_mut().for_each(|s| *s = 0.02);
+
+        let mut commits = Vec::new();
+        for chunk in speech.chunks(4800) {
+            for action in state.process_audio_chunk(chunk) {
+                if let Action::SubmitVadRequest { audio, request_id } = action {
+                    commits.push((audio.len(), request_id));
+                }
+            }
+        }
+
+        // The first part ends in the dip, and the rest (plus overlap) stays buffered
+        assert_eq!(commits.len(), 1);
+        let (first_len, first_id) = commits[0];
+        assert!((dip..dip + 640).contains(&first_len), "cut at {}", first_len);
+        assert_eq!(state.vad_buffer.len(), speech.len() - first_len + vad::SPLIT_OVERLAP_SAMPLES);
+
+        let second_id = state.finish(&[]).iter().find_map(|action| match action {
+            Action::SubmitVadRequest { request_id, .. } => Some(*request_id),
+            _ => None,
+        }).expect("expected a VAD commit");
+
+        // The word heard in both parts is typed once
+        state.process_vad_result("we kept on talking".to_string(), first_id);
+        state.process_vad_result("Talking without a pause.".to_string(), second_id);
+        assert_eq!(state.committed_text(), "we kept on talking without a pause. ");
+    }
+
+    #[test]
+    fn test_live_preview_limited_to_buffer_tail() {
+        let mut state = TranscriptionState::new(0.01);
+        let mut longest_preview = 0;
+
+        for _ in 0..60 {
+            for action in state.process_audio_chunk(&create_speech_audio(300)) {
+                if let Action::SubmitLiveRequest { audio, request_id } = action {
+                    longest_preview = longest_preview.max(audio.len());
+                    state.process_live_result(format!("words up to {}", state.vad_buffer.len()), request_id);
+                }
+            }
+        }
+
+        // 18s of speech, but previews never cover much more than the maximum window
+        assert_eq!(state.vad_buffer.len(), 60 * 4800);
+        assert!(longest_preview <= streaming::LIVE_PREVIEW_MAX_SAMPLES + 4800, "{}", longest_preview);
+
+        // Text of earlier windows stays on screen ahead of the current window
+        assert!(state.live_preview_text.starts_with("words up to"));
+        assert!(state.live_preview_text.matches("words up to").count() >= 2, "{}", state.live_preview_text);
+    }
+
+    /// Apply a keyboard action to simulated screen text, failing if it deletes anything
+    fn type_without_deleting(screen: &mut String, action: Action) {
+        match action {
+            Action::AppendText(text) => screen.push_str(&text),
+            Action::ReplaceText { chars_to_delete: 0, new_text } => screen.push_str(&new_text),
+            Action::NoAction => {}
+            other => panic!("typed text was revised: {:?}", other),
+        }
+    }
+
+    /// Speak one chunk and answer any live preview with the next scripted hypothesis
+    fn speak_with_hypotheses(state: &mut TranscriptionState, screen: &mut String, hypotheses: &mut std::slice::Iter<&str>) {
+        for action in state.process_audio_chunk(&create_speech_audio(300)) {
+            if let Action::SubmitLiveRequest { request_id, .. } = action {
+                let hypothesis = hypotheses.next().expect("ran out of scripted hypotheses");
+                type_without_deleting(screen, state.process_live_result(hypothesis.to_string(), request_id));
+            }
+        }
+    }
+
+    #[test]
+    fn test_local_agreement_types_only_agreed_words() {
+        let config = TranscriptionConfig {
+            commit_policy: CommitPolicy::LocalAgreement,
+            ..TranscriptionConfig::default()
+        };
+        let mut state = TranscriptionState::from_config(&config);
+        let mut screen = String::new();
+
+        let script = ["I", "I think", "I think we", "I thing we should", "I think we should go"];
+        let mut hypotheses = script.iter();
+        let mut shown = Vec::new();
+        while hypotheses.len() > 0 {
+            speak_with_hypotheses(&mut state, &mut screen, &mut hypotheses);
+            if shown.last() != Some(&screen) {
+                shown.push(screen.clone());
+            }
+        }
+        // "thing" breaks agreement, so "we" is left to the VAD transcription
+        assert_eq!(shown, vec!["", "I", "I think"]);
+
+        // The pause commits the utterance; the VAD transcription supplies the unconfirmed rest
+        let request_id = (0..StreamingTiming::default().commit_silence_chunks)
+            .flat_map(|_| state.process_audio_chunk(&create_silence_audio(300)))
+            .find_map(|action| match action {
+                Action::SubmitVadRequest { request_id, .. } => Some(request_id),
+                _ => None,
+            })
+            .expect("expected a VAD commit");
+
+        // The next utterance's agreed words are typed before the first one is transcribed
+        let mut next = ["Then", "Then we", "Then we left"].iter();
+        while next.len() > 0 {
+            speak_with_hypotheses(&mut state, &mut screen, &mut next);
+        }
+        assert_eq!(screen, "I think Then we");
+
+        let action = state.process_vad_result("I think we should go.".to_string(), request_id);
+        let Action::ReplaceText { chars_to_delete, new_text } = action else {
+            panic!("expected the rest to be inserted, got {:?}", action);
+        };
+        crate::output::apply_edit(&mut screen, chars_to_delete, &new_text);
+        assert_eq!(screen, "I think we should go. Then we");
+        assert_eq!(state.committed_text(), "I think we should go. ");
+    }
+
+    #[test]
+    fn test_cancel_erases_session_text() {
+        let mut state = TranscriptionState::new(0.01);
+        state.push_pending_commit(1, Vec::new());
+        state.process_vad_result("Hello world".to_string(), 1);
+        state.pending_live_request = Some(2);
+        state.process_live_result("and then".to_string(), 2);
+        state.push_pending_commit(3, Vec::new());
+
+        let actions = state.cancel();
+        assert_eq!(actions, vec![
+            Action::ReplaceText {
+                chars_to_delete: "Hello world and then".chars().count(),
+                new_text: String::new(),
+            },
+            Action::CancelAllRequests { before: state.next_request_id },
+        ]);
+        assert!(state.is_finished());
+
+        // The cancelled commit's result arrives later and is ignored
+        assert_eq!(state.process_vad_result("too late".to_string(), 3), Action::NoAction);
+        assert_eq!(state.committed_text(), "");
+    }
+
+    #[test]
+    fn test_resume_after_pause_continues_text() {
+        let mut state = TranscriptionState::new(0.01);
+        state.process_audio_chunk(&create_speech_audio(300));
+        let request_id = state.pause(&create_speech_audio(300)).into_iter()
+            .find_map(|action| match action {
+                Action::SubmitVadRequest { request_id, .. } => Some(request_id),
+                _ => None,
+            })
+            .expect("pause should flush the utterance");
+        assert!(state.vad_buffer.is_empty() && !state.is_finished()); // Flushed, awaiting its text
+        assert_eq!(state.process_vad_result("Before the break.".to_string(), request_id),
+                   Action::AppendText("Before the break. ".to_string()));
+
+        // Resuming continues the same text
+        state.process_audio_chunk(&create_speech_audio(300));
+        let request_id = state.finish(&[]).into_iter()
+            .find_map(|action| match action {
+                Action::SubmitVadRequest { request_id, .. } => Some(request_id),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(state.process_vad_result("And after.".to_string(), request_id),
+                   Action::AppendText("And after. ".to_string()));
+        assert_eq!(state.committed_text(), "Before the break. And after. ");
+    }
+
+    #[test]
+    fn test_pre_roll_starts_first_utterance() {
+        let mut state = TranscriptionState::new(0.01);
+        state.set_pre_roll(create_speech_audio(200));
+        state.process_audio_chunk(&create_speech_audio(300));
+        assert_eq!(state.vad_buffer.len(), 500 * 16);
+
+        // Only the first utterance of a session gets it
+        state.reset();
+        state.set_pre_roll(create_speech_audio(200));
+        state.process_audio_chunk(&create_silence_audio(300));
+        state.process_audio_chunk(&create_speech_audio(300));
+        assert_eq!(state.vad_buffer.len(), 300 * 16);
+    }
+
+    #[test]
+    fn test_finish_ignores_trailing_silence() {
+        let mut state = TranscriptionState::new(0.01);
+        assert!(state.finish(&create_silence_audio(200)).is_empty());
+        assert!(state.is_finished());
+    }
+
+    #[test]
+    fn test_effective_threshold_from_config() {
+        let config = TranscriptionConfig {
+            silence_threshold: 0.003,
+            adaptive_threshold: true,
+            ..TranscriptionConfig::default()
+        };
+        let mut state = TranscriptionState::from_config(&config);
+        assert_eq!(state.effective_threshold(), 0.003);
+
+        // Low-level hum calibrates the threshold below the fixed default
+        let hum: Vec<f32> = (0..4800).map(|i| 0.0005 * (i as f32 * 0.3).sin()).collect();
+        state.process_audio_chunk(&hum);
+        assert!(state.effective_threshold() < 0.003);
+
+        state.reset();
+        assert_eq!(state.effective_threshold(), 0.003);
+    }
+
+    #[test]
+    fn test_timing_follows_chunk_duration() {
+        let config = TranscriptionConfig {
+            chunk_duration_ms: 100,
+            ..TranscriptionConfig::default()
+        };
+        let mut state = TranscriptionState::from_config(&config);
+
+        // The same 1.5s preview delay is 15 chunks of 100ms
+        let live_after = (1..=20)
+            .find(|_| state.process_audio_chunk(&create_speech_audio(100)).iter()
+                .any(|action| matches!(action, Action::SubmitLiveRequest { .. })));
+        assert_eq!(live_after, Some(15));
+
+        // 1.5s of silence commits, keeping 600ms of it
+        let commit_after = (1..=20)
+            .find(|_| state.process_audio_chunk(&create_silence_audio(100)).iter()
+                .any(|action| matches!(action, Action::SubmitVadRequest { .. })));
+        assert_eq!(commit_after, Some(15));
+        let committed = &state.pending_vad_requests[0].audio;
+        assert_eq!(committed.len(), (1500 + 600) * 16);
+    }
+
+    #[test]
+    fn test_reconfigure_keeps_request_ids() {
+        let mut state = TranscriptionState::from_config(&TranscriptionConfig::default());
+        for _ in 0..5 {
+            state.process_audio_chunk(&create_speech_audio(300));
+        }
+        let cancelled = state.cancel();
+        assert!(matches!(cancelled.last(), Some(Action::CancelAllRequests { before: 2 })));
+
+        let config = TranscriptionConfig {
+            commit_silence_ms: 3000,
+            ..TranscriptionConfig::default()
+        };
+        state.reconfigure(&config);
+        assert_eq!(state.timing.commit_silence_chunks, 10);
+
+        // IDs below the cancellation watermark are never reused
+        let actions: Vec<Action> = (0..5)
+            .flat_map(|_| state.process_audio_chunk(&create_speech_audio(300)))
+            .collect();
+        let Some(Action::SubmitLiveRequest { request_id, .. }) = actions.first() else {
+            panic!("expected a live request, got {:?}", actions);
+        };
+        assert!(*request_id >= 2);
+    }
+
     #[test]
     fn test_request_id_ignored_if_not_pending() {
         let mut state = TranscriptionState::new(0.01);
-        state.pending_vad_request = Some(1);
+        state.push_pending_commit(1, Vec::new());
 
         let action = state.process_vad_result("test".to_string(), 2);
 
         assert_eq!(action, Action::NoAction);
-        assert_eq!(state.pending_vad_request, Some(1)); // Unchanged
+        assert_eq!(state.pending_vad_requests.len(), 1); // Unchanged
+        assert_eq!(state.pending_vad_requests[0].text, None);
     }
 }
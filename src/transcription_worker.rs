@@ -1,17 +1,49 @@
-use std::sync::mpsc::{channel, sync_channel, Sender, SyncSender, Receiver, TrySendError};
+use std::collections::HashMap;
+use std::sync::mpsc::{channel, sync_channel, Sender, SyncSender, Receiver, TryRecvError, TrySendError};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
 use std::thread;
-use std::collections::HashSet;
+use std::time::Duration;
 use anyhow::Result;
+use whisper_rs::WhisperContext;
+use crate::config::TranscriptionConfig;
 use crate::transcription::TranscriberWithState;
 use crate::constants::worker::MAX_PENDING_REQUESTS;
 
 /// Message sent to worker threads
-#[derive(Debug)]
 enum WorkerMessage {
-    /// Transcribe audio with given request ID
-    Transcribe { audio: Vec<f32>, request_id: u64 },
-    /// Cancel all requests before a given ID
-    CancelAllBefore { request_id: u64 },
+    /// Transcribe audio with given request ID, optionally with the fallback model
+    Transcribe { audio: Vec<f32>, request_id: u64, use_fallback: bool },
+    /// Use a different model or language for the requests that follow
+    Reconfigure { transcriber: TranscriberWithState },
+}
+
+/// A model being loaded on a background thread, so the app keeps running meanwhile
+pub struct PendingModel {
+    /// Settings the model is loaded with
+    pub config: TranscriptionConfig,
+    receiver: Receiver<Result<Arc<WhisperContext>>>,
+}
+
+impl PendingModel {
+    pub fn load(config: TranscriptionConfig) -> Self {
+        let (sender, receiver) = channel();
+        let thread_config = config.clone();
+        thread::spawn(move || {
+            // Nobody is waiting anymore if another model was requested meanwhile
+            let _ = sender.send(TranscriberWithState::load_context(&thread_config));
+        });
+        PendingModel { config, receiver }
+    }
+
+    /// The loaded model or the error, once loading has finished (non-blocking)
+    pub fn poll(&self) -> Option<Result<Arc<WhisperContext>>> {
+        match self.receiver.try_recv() {
+            Ok(result) => Some(result),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Err(anyhow::anyhow!("Model loading thread stopped"))),
+        }
+    }
 }
 
 /// Result of a transcription
@@ -23,12 +55,29 @@ pub enum TranscriptionResult {
     VadCommit { text: String, request_id: u64 },
     /// Error during transcription
     Error { error: String, request_id: u64 },
+    /// Request was cancelled before or while it was transcribed
+    Cancelled { request_id: u64 },
 }
 
 /// Handle for communicating with the transcription worker threads
 pub struct TranscriptionWorker {
     live_task_sender: SyncSender<WorkerMessage>,
-    vad_task_sender: SyncSender<WorkerMessage>,
+    vad_task_sender: Sender<WorkerMessage>,
+    /// Used to report requests that never reach a worker, so every ID gets a result
+    result_sender: Sender<TranscriptionResult>,
+    /// VAD commits queued or running; live previews yield the model while this is non-zero
+    vad_in_flight: Arc<AtomicUsize>,
+    /// Live requests with IDs below this watermark are cancelled
+    ///
+    /// Shared with the worker thread so a running transcription sees it immediately,
+    /// rather than after it finishes and reads the next message.
+    live_cancel_watermark: Arc<AtomicU64>,
+    /// VAD commits with IDs below this watermark are cancelled (the session was discarded)
+    vad_cancel_watermark: Arc<AtomicU64>,
+    /// Settings both threads are using
+    config: TranscriptionConfig,
+    /// Loaded models by name, including the one in use, so profiles switch without reloading
+    models: HashMap<String, Arc<WhisperContext>>,
 }
 
 impl TranscriptionWorker {
@@ -36,11 +85,13 @@ impl TranscriptionWorker {
     pub fn new(
         shared_transcriber: TranscriberWithState,
     ) -> Result<(Self, Receiver<TranscriptionResult>)> {
-        // Use bounded channels to prevent unbounded memory growth
-        // MAX_PENDING_REQUESTS ensures backpressure when workers are slow
+        // Live previews use a bounded channel: stale previews are worthless, so they're dropped
+        // when the worker is busy. VAD commits are dictated speech and must never be dropped,
+        // so their channel is unbounded (a minute of queued audio is under 4MB).
         let (live_task_tx, live_task_rx) = sync_channel(MAX_PENDING_REQUESTS);
-        let (vad_task_tx, vad_task_rx) = sync_channel(MAX_PENDING_REQUESTS);
+        let (vad_task_tx, vad_task_rx) = channel();
         let (result_tx, result_rx) = channel(); // Results channel can be unbounded
+        let vad_in_flight = Arc::new(AtomicUsize::new(0));
 
         // Extract shared context and config from the transcriber
         let shared_ctx = shared_transcriber.ctx.clone();
@@ -54,99 +105,208 @@ impl TranscriptionWorker {
         )?;
 
         let vad_transcriber = TranscriberWithState::new_with_shared_context(
-            shared_ctx,
-            config
+            shared_ctx.clone(),
+            config.clone()
         )?;
 
         // Spawn live preview worker thread
         let result_tx_live = result_tx.clone();
+        let live_cancel_watermark = Arc::new(AtomicU64::new(0));
+        let live_watermark = live_cancel_watermark.clone();
+        let live_vad_in_flight = vad_in_flight.clone();
         thread::spawn(move || {
-            Self::live_worker_loop(live_task_rx, result_tx_live, live_transcriber);
+            Self::live_worker_loop(live_task_rx, result_tx_live, live_transcriber, live_watermark, live_vad_in_flight);
         });
 
         // Spawn VAD worker thread
+        let result_tx_vad = result_tx.clone();
+        let vad_cancel_watermark = Arc::new(AtomicU64::new(0));
+        let vad_watermark = vad_cancel_watermark.clone();
+        let worker_vad_in_flight = vad_in_flight.clone();
         thread::spawn(move || {
-            Self::vad_worker_loop(vad_task_rx, result_tx, vad_transcriber);
+            Self::vad_worker_loop(vad_task_rx, result_tx_vad, vad_transcriber, vad_watermark, worker_vad_in_flight);
         });
 
         let worker = TranscriptionWorker {
             live_task_sender: live_task_tx,
             vad_task_sender: vad_task_tx,
+            result_sender: result_tx,
+            vad_in_flight,
+            live_cancel_watermark,
+            vad_cancel_watermark,
+            models: HashMap::from([(config.model.clone(), shared_ctx)]),
+            config,
         };
 
         Ok((worker, result_rx))
     }
 
+    /// Switch both threads to other settings (e.g. a profile's) if their model is loaded
+    ///
+    /// Requests already queued finish with the previous settings. Returns false, leaving
+    /// the worker unchanged, if the model has to be loaded first (see `PendingModel`).
+    /// `use_gpu` is the same for every model, so models are looked up by name.
+    pub fn reconfigure(&mut self, config: &TranscriptionConfig) -> Result<bool> {
+        if config.model == self.config.model
+            && config.language == self.config.language
+            && config.fallback_model == self.config.fallback_model
+        {
+            return Ok(true);
+        }
+
+        let Some(ctx) = self.models.get(&config.model).cloned() else {
+            return Ok(false);
+        };
+        self.use_context(ctx, config)?;
+        Ok(true)
+    }
+
+    /// Switch both threads to a model that's already loaded (see `PendingModel`)
+    ///
+    /// The model is kept for later switches until `release_models` frees it.
+    pub fn use_context(&mut self, ctx: Arc<WhisperContext>, config: &TranscriptionConfig) -> Result<()> {
+        let live_transcriber = TranscriberWithState::new_with_shared_context(ctx.clone(), config.clone())?;
+        let vad_transcriber = TranscriberWithState::new_with_shared_context(ctx.clone(), config.clone())?;
+
+        // Blocks only while the live queue is full, which the worker drains quickly
+        if self.live_task_sender.send(WorkerMessage::Reconfigure { transcriber: live_transcriber }).is_err()
+            || self.vad_task_sender.send(WorkerMessage::Reconfigure { transcriber: vad_transcriber }).is_err()
+        {
+            anyhow::bail!("Transcription worker disconnected");
+        }
+
+        self.models.insert(config.model.clone(), ctx);
+        self.config = config.clone();
+        Ok(())
+    }
+
+    /// Keep a model loaded in the background for later switches, without using it yet
+    pub fn keep_model(&mut self, model: &str, ctx: Arc<WhisperContext>) {
+        self.models.insert(model.to_string(), ctx);
+    }
+
+    /// Free the loaded models other than the one in use and those named in `keep`
+    ///
+    /// A model the threads are still using is freed once they've moved on from it.
+    pub fn release_models(&mut self, keep: &[&str]) {
+        let current = &self.config.model;
+        self.models.retain(|model, _| model == current || keep.contains(&model.as_str()));
+    }
+
     /// Submit a live preview transcription request with a specific request ID (non-blocking)
     ///
-    /// Uses try_send to avoid blocking the event loop. If the queue is full, the request is dropped.
+    /// Uses try_send to avoid blocking the event loop. If the queue is full, the request is dropped
+    /// and reported back as an error.
     pub fn transcribe_live_preview_with_id(&self, audio: Vec<f32>, request_id: u64) {
-        match self.live_task_sender.try_send(WorkerMessage::Transcribe { audio, request_id }) {
+        match self.live_task_sender.try_send(WorkerMessage::Transcribe { audio, request_id, use_fallback: false }) {
             Ok(_) => {},
             Err(TrySendError::Full(_)) => {
                 // Queue is full - drop this request since we want real-time performance
                 // This is actually desirable: we don't want to block on old audio
                 eprintln!("⚠️  Live preview queue full, dropping request {} (worker is busy)", request_id);
+                self.report_unsent(request_id, "Live preview queue full");
             },
             Err(TrySendError::Disconnected(_)) => {
                 eprintln!("❌ Live preview worker disconnected");
+                self.report_unsent(request_id, "Live preview worker disconnected");
             }
         }
     }
 
     /// Cancel all live preview requests before a given ID
+    ///
+    /// Queued requests are skipped and a running transcription is aborted. The watermark
+    /// only moves forward, so later requests are never affected.
     pub fn cancel_all_live_before(&self, request_id: u64) {
-        if let Err(e) = self.live_task_sender.send(WorkerMessage::CancelAllBefore { request_id }) {
-            eprintln!("⚠️  Failed to send cancel-all request: {}", e);
+        let previous = self.live_cancel_watermark.fetch_max(request_id, Ordering::SeqCst);
+        if request_id > previous {
+            println!("❌ Cancelling live requests before {}", request_id);
+        }
+    }
+
+    /// Cancel all live preview and VAD commit requests before a given ID
+    ///
+    /// Used when a session is discarded: its queued commits are skipped and a running one
+    /// is aborted, so the next session doesn't wait for them.
+    pub fn cancel_all_before(&self, request_id: u64) {
+        self.cancel_all_live_before(request_id);
+        let previous = self.vad_cancel_watermark.fetch_max(request_id, Ordering::SeqCst);
+        if request_id > previous {
+            println!("❌ Cancelling VAD commits before {}", request_id);
         }
     }
 
     /// Submit a VAD commit transcription request with a specific request ID (non-blocking)
     ///
-    /// Uses try_send to avoid blocking the event loop. If the queue is full, the request is dropped.
+    /// The VAD queue is unbounded, so the request is never dropped. It takes priority over
+    /// live previews, which wait (or abort) while a VAD commit is queued or running.
     pub fn transcribe_vad_commit_with_id(&self, audio: Vec<f32>, request_id: u64) {
-        match self.vad_task_sender.try_send(WorkerMessage::Transcribe { audio, request_id }) {
-            Ok(_) => {},
-            Err(TrySendError::Full(_)) => {
-                // Queue is full - this shouldn't happen often for VAD commits
-                // VAD commits are important, so we warn loudly
-                eprintln!("⚠️  VAD commit queue full, dropping request {} (worker overloaded!)", request_id);
-            },
-            Err(TrySendError::Disconnected(_)) => {
-                eprintln!("❌ VAD commit worker disconnected");
-            }
+        self.submit_vad(audio, request_id, false);
+    }
+
+    /// Resubmit a failed VAD commit, optionally with the fallback model
+    pub fn retry_vad_commit_with_id(&self, audio: Vec<f32>, request_id: u64, use_fallback: bool) {
+        self.submit_vad(audio, request_id, use_fallback);
+    }
+
+    fn submit_vad(&self, audio: Vec<f32>, request_id: u64, use_fallback: bool) {
+        self.vad_in_flight.fetch_add(1, Ordering::SeqCst);
+        if self.vad_task_sender.send(WorkerMessage::Transcribe { audio, request_id, use_fallback }).is_err() {
+            self.vad_in_flight.fetch_sub(1, Ordering::SeqCst);
+            eprintln!("❌ VAD commit worker disconnected");
+            self.report_unsent(request_id, "VAD commit worker disconnected");
         }
     }
 
+    /// Report a request that couldn't be queued, so the state machine stops waiting for it
+    fn report_unsent(&self, request_id: u64, error: &str) {
+        let _ = self.result_sender.send(TranscriptionResult::Error {
+            error: error.to_string(),
+            request_id,
+        });
+    }
+
     /// Live preview worker thread - handles fast live transcriptions with cancellation support
     fn live_worker_loop(
         task_rx: Receiver<WorkerMessage>,
         result_tx: Sender<TranscriptionResult>,
         mut transcriber: TranscriberWithState,
+        cancel_watermark: Arc<AtomicU64>,
+        vad_in_flight: Arc<AtomicUsize>,
     ) {
         println!("🔧 Live preview worker thread started");
 
-        let mut cancelled_ids: HashSet<u64> = HashSet::new();
-
         for message in task_rx {
             match message {
-                WorkerMessage::Transcribe { audio, request_id } => {
-                    // Check if this request was cancelled
-                    if cancelled_ids.contains(&request_id) {
-                        println!("⏭️  Skipping cancelled live request {}", request_id);
-                        cancelled_ids.remove(&request_id);
-                        continue;
+                WorkerMessage::Transcribe { audio, request_id, .. } => {
+                    let is_cancelled = || request_id < cancel_watermark.load(Ordering::SeqCst);
+                    let vad_busy = || vad_in_flight.load(Ordering::SeqCst) > 0;
+
+                    // Let pending VAD commits use the model first
+                    while vad_busy() && !is_cancelled() {
+                        thread::sleep(Duration::from_millis(10));
                     }
 
-                    let result = match transcriber.transcribe(&audio) {
-                        Ok(text) => TranscriptionResult::LivePreview {
-                            text: text.trim().to_string(),
-                            request_id,
-                        },
-                        Err(e) => TranscriptionResult::Error {
-                            error: format!("Live preview error: {}", e),
-                            request_id,
-                        },
+                    // Skip requests that were cancelled while queued; give up the model
+                    // mid-run if a VAD commit arrives (the next chunk submits a fresh preview)
+                    let result = if is_cancelled() {
+                        println!("⏭️  Skipping cancelled live request {}", request_id);
+                        TranscriptionResult::Cancelled { request_id }
+                    } else {
+                        match transcriber.transcribe_with_abort(&audio, &mut || is_cancelled() || vad_busy()) {
+                            Ok(Some(text)) => TranscriptionResult::LivePreview {
+                                text: text.trim().to_string(),
+                                request_id,
+                            },
+                            Ok(None) => {
+                                println!("⏹️  Aborted live request {}", request_id);
+                                TranscriptionResult::Cancelled { request_id }
+                            }
+                            Err(e) => TranscriptionResult::Error {
+                                error: format!("Live preview error: {}", e),
+                                request_id,
+                            },
+                        }
                     };
 
                     if result_tx.send(result).is_err() {
@@ -154,11 +314,8 @@ impl TranscriptionWorker {
                         break;
                     }
                 }
-                WorkerMessage::CancelAllBefore { request_id } => {
-                    // Cancel all requests with IDs less than the given ID
-                    // In practice, we just clear the set since requests are processed in order
-                    cancelled_ids.clear();
-                    println!("❌ Cancelled all live requests before {}", request_id);
+                WorkerMessage::Reconfigure { transcriber: new_transcriber } => {
+                    transcriber = new_transcriber;
                 }
             }
         }
@@ -171,40 +328,72 @@ impl TranscriptionWorker {
         task_rx: Receiver<WorkerMessage>,
         result_tx: Sender<TranscriptionResult>,
         mut transcriber: TranscriberWithState,
+        cancel_watermark: Arc<AtomicU64>,
+        vad_in_flight: Arc<AtomicUsize>,
     ) {
         println!("🔧 VAD worker thread started");
 
-        let mut cancelled_ids: HashSet<u64> = HashSet::new();
+        // The fallback model is only loaded if a retry needs it
+        let fallback_for = |config: &TranscriptionConfig| {
+            config.fallback_model.clone().map(|model| TranscriptionConfig {
+                model,
+                ..config.clone()
+            })
+        };
+        let mut fallback_config = fallback_for(&transcriber.config);
+        let mut fallback_transcriber: Option<TranscriberWithState> = None;
 
         for message in task_rx {
             match message {
-                WorkerMessage::Transcribe { audio, request_id } => {
-                    // Check if this request was cancelled
-                    if cancelled_ids.contains(&request_id) {
-                        println!("⏭️  Skipping cancelled VAD request {}", request_id);
-                        cancelled_ids.remove(&request_id);
-                        continue;
-                    }
+                WorkerMessage::Transcribe { audio, request_id, use_fallback } => {
+                    let mut is_cancelled = || request_id < cancel_watermark.load(Ordering::SeqCst);
+
+                    let outcome = if is_cancelled() {
+                        Ok(None)
+                    } else {
+                        match (&fallback_config, use_fallback) {
+                            (Some(config), true) => {
+                                if fallback_transcriber.is_none() {
+                                    println!("🔧 Loading fallback model {}", config.model);
+                                    fallback_transcriber = TranscriberWithState::new(config.clone())
+                                        .map_err(|e| eprintln!("❌ Failed to load fallback model: {:#}", e))
+                                        .ok();
+                                }
+                                // Use the primary model if the fallback failed to load
+                                fallback_transcriber
+                                    .as_mut()
+                                    .unwrap_or(&mut transcriber)
+                                    .transcribe_with_abort(&audio, &mut is_cancelled)
+                            }
+                            _ => transcriber.transcribe_with_abort(&audio, &mut is_cancelled),
+                        }
+                    };
 
-                    let result = match transcriber.transcribe(&audio) {
-                        Ok(text) => TranscriptionResult::VadCommit {
+                    let result = match outcome {
+                        Ok(Some(text)) => TranscriptionResult::VadCommit {
                             text: text.trim().to_string(),
                             request_id,
                         },
+                        Ok(None) => {
+                            println!("⏭️  Cancelled VAD commit {}", request_id);
+                            TranscriptionResult::Cancelled { request_id }
+                        }
                         Err(e) => TranscriptionResult::Error {
                             error: format!("VAD commit error: {}", e),
                             request_id,
                         },
                     };
 
+                    vad_in_flight.fetch_sub(1, Ordering::SeqCst);
                     if result_tx.send(result).is_err() {
                         println!("⚠️  VAD worker: main thread disconnected");
                         break;
                     }
                 }
-                WorkerMessage::CancelAllBefore { request_id } => {
-                    cancelled_ids.clear();
-                    println!("❌ Cancelled all VAD requests before {}", request_id);
+                WorkerMessage::Reconfigure { transcriber: new_transcriber } => {
+                    fallback_config = fallback_for(&new_transcriber.config);
+                    fallback_transcriber = None;
+                    transcriber = new_transcriber;
                 }
             }
         }
@@ -1,14 +1,18 @@
 use anyhow::{Context, Result};
 use tray_icon::{
-    menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem},
+    menu::{CheckMenuItem, Menu, MenuEvent, MenuItem, PredefinedMenuItem, Submenu},
     TrayIcon, TrayIconBuilder,
 };
 use image::{Rgba, RgbaImage};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TrayMenuEvent {
     StartTranscription,
     StopTranscription,
+    /// Pause recording, or resume a paused session
+    PauseTranscription,
+    /// Load this model in the background and switch to it
+    SwitchModel(String),
     Settings,
     Quit,
 }
@@ -17,6 +21,9 @@ pub struct TrayApp {
     tray_icon: TrayIcon,
     start_item: MenuItem,
     stop_item: MenuItem,
+    pause_item: MenuItem,
+    /// One per installed model, checked for the model in use
+    model_items: Vec<(String, CheckMenuItem)>,
     settings_item: MenuItem,
     base_icon: tray_icon::Icon,
     recording_icon: tray_icon::Icon,
@@ -24,7 +31,7 @@ pub struct TrayApp {
 }
 
 impl TrayApp {
-    pub fn new() -> Result<Self> {
+    pub fn new(models: &[String], current_model: &str) -> Result<Self> {
         // Create base icon - waveform bars (white on transparent)
         let size = 32u32;
         let base_icon = Self::create_base_icon(size)?;
@@ -37,11 +44,22 @@ impl TrayApp {
 
         let start_item = MenuItem::new("Start Transcription", true, None);
         let stop_item = MenuItem::new("Stop Transcription", false, None);
+        let pause_item = MenuItem::new("Pause Transcription", false, None);
         let settings_item = MenuItem::new("Settings", true, None);
 
+        let model_menu = Submenu::new("Model", true);
+        let mut model_items = Vec::with_capacity(models.len());
+        for model in models {
+            let item = CheckMenuItem::new(model, true, model == current_model, None);
+            model_menu.append(&item)?;
+            model_items.push((model.clone(), item));
+        }
+
         menu.append(&start_item)?;
         menu.append(&stop_item)?;
+        menu.append(&pause_item)?;
         menu.append(&PredefinedMenuItem::separator())?;
+        menu.append(&model_menu)?;
         menu.append(&settings_item)?;
         menu.append(&PredefinedMenuItem::separator())?;
         menu.append(&PredefinedMenuItem::quit(Some("Quit")))?;
@@ -58,6 +76,8 @@ impl TrayApp {
             tray_icon,
             start_item,
             stop_item,
+            pause_item,
+            model_items,
             settings_item,
             base_icon,
             recording_icon,
@@ -155,6 +175,13 @@ impl TrayApp {
     pub fn set_transcribing(&mut self, is_transcribing: bool) {
         self.start_item.set_enabled(!is_transcribing);
         self.stop_item.set_enabled(is_transcribing);
+        self.pause_item.set_enabled(is_transcribing);
+        self.pause_item.set_text("Pause Transcription");
+
+        // A new recording clears the warning from the previous one
+        if is_transcribing {
+            self.clear_warning();
+        }
 
         // If stopping transcription, reset to base icon
         if !is_transcribing {
@@ -163,6 +190,34 @@ impl TrayApp {
         }
     }
 
+    /// Show a paused session: recording is off, but it can be resumed or stopped
+    pub fn set_paused(&mut self) {
+        self.start_item.set_enabled(false);
+        self.stop_item.set_enabled(true);
+        self.pause_item.set_enabled(true);
+        self.pause_item.set_text("Resume Transcription");
+        let _ = self.tray_icon.set_icon(Some(self.base_icon.clone()));
+        self.is_recording_visible = false;
+    }
+
+    /// Check the model in use (clicking a model toggles its check mark by itself)
+    pub fn set_current_model(&mut self, current_model: &str) {
+        for (model, item) in &self.model_items {
+            item.set_checked(model == current_model);
+        }
+    }
+
+    /// Show a warning next to the icon (macOS) and in its tooltip
+    pub fn show_warning(&mut self, message: &str) {
+        self.tray_icon.set_title(Some("⚠️"));
+        let _ = self.tray_icon.set_tooltip(Some(format!("Live Transcribe - {}", message)));
+    }
+
+    fn clear_warning(&mut self) {
+        self.tray_icon.set_title(None::<&str>);
+        let _ = self.tray_icon.set_tooltip(Some("Live Transcribe"));
+    }
+
     /// Toggle the recording indicator (call this periodically for blinking effect)
     pub fn blink_recording_indicator(&mut self) {
         self.is_recording_visible = !self.is_recording_visible;
@@ -182,6 +237,10 @@ impl TrayApp {
                 return Some(TrayMenuEvent::StartTranscription);
             } else if id == self.stop_item.id() {
                 return Some(TrayMenuEvent::StopTranscription);
+            } else if id == self.pause_item.id() {
+                return Some(TrayMenuEvent::PauseTranscription);
+            } else if let Some((model, _)) = self.model_items.iter().find(|(_, item)| id == item.id()) {
+                return Some(TrayMenuEvent::SwitchModel(model.clone()));
             } else if id == self.settings_item.id() {
                 return Some(TrayMenuEvent::Settings);
             } else if id.0 == "quit" {
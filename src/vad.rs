@@ -0,0 +1,539 @@
+//! Voice activity detection
+//!
+//! The transcription state machine asks a `VoiceActivityDetector` whether each audio chunk
+//! contains speech. Two detectors are available:
+//!
+//! - `RmsVad`: the original energy threshold. Cheap, but any steady noise above the
+//!   threshold (fans, HVAC, keyboards) counts as speech.
+//! - `SpectralVad`: looks at 20ms frames and requires energy above a tracked noise floor,
+//!   a speech-like zero-crossing rate, and a peaky (non-flat) spectrum in the speech band.
+//!
+//! With `adaptive_threshold` enabled, the RMS detector derives its threshold from a
+//! `NoiseFloorTracker` instead of the fixed `silence_threshold`.
+
+use serde::{Deserialize, Serialize};
+use crate::config::TranscriptionConfig;
+
+const SAMPLE_RATE: f32 = 16000.0;
+
+/// Decides whether an audio chunk (16kHz mono) contains speech
+pub trait VoiceActivityDetector: Send {
+    /// Returns true if the chunk contains speech
+    fn is_speech(&mut self, audio: &[f32]) -> bool;
+
+    /// Forget state carried over from previous chunks (called when a session starts)
+    fn reset(&mut self) {}
+
+    /// Current RMS threshold for speech, if the detector uses one
+    fn threshold(&self) -> Option<f32> {
+        None
+    }
+}
+
+/// Which voice activity detector to use
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum VadKind {
+    /// Plain RMS energy threshold
+    Rms,
+    /// Energy, zero-crossing rate and spectral flatness on 20ms frames
+    Spectral,
+}
+
+/// Build the detector selected in the configuration
+pub fn build_detector(config: &TranscriptionConfig) -> Box<dyn VoiceActivityDetector> {
+    match config.vad {
+        VadKind::Rms if config.adaptive_threshold => Box::new(AdaptiveRmsVad::new(
+            config.silence_threshold,
+            config.calibration_ms,
+        )),
+        VadKind::Rms => Box::new(RmsVad::new(config.silence_threshold)),
+        VadKind::Spectral => Box::new(SpectralVad::new(config.silence_threshold)),
+    }
+}
+
+/// Root mean square energy of an audio buffer
+pub fn rms(audio: &[f32]) -> f32 {
+    if audio.is_empty() {
+        return 0.0;
+    }
+    let sum_squares: f32 = audio.iter().map(|&x| x * x).sum();
+    (sum_squares / audio.len() as f32).sqrt()
+}
+
+/// Offset of the middle of the quietest `frame_len`-sample frame in `audio`
+///
+/// Used to split long utterances where the cut is least likely to land inside a word.
+pub fn quietest_point(audio: &[f32], frame_len: usize) -> usize {
+    let frame_len = frame_len.max(1);
+    audio
+        .chunks(frame_len)
+        .map(rms)
+        .enumerate()
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(index, _)| (index * frame_len + frame_len / 2).min(audio.len()))
+        .unwrap_or(0)
+}
+
+/// Energy-based detector: speech if the chunk's RMS reaches the threshold
+pub struct RmsVad {
+    threshold: f32,
+}
+
+impl RmsVad {
+    pub fn new(threshold: f32) -> Self {
+        Self { threshold }
+    }
+}
+
+impl VoiceActivityDetector for RmsVad {
+    fn is_speech(&mut self, audio: &[f32]) -> bool {
+        !audio.is_empty() && rms(audio) >= self.threshold
+    }
+
+    fn threshold(&self) -> Option<f32> {
+        Some(self.threshold)
+    }
+}
+
+/// Speech must be this many times louder than the estimated noise floor
+const ADAPTIVE_MARGIN: f32 = 3.0;
+
+/// Bounds for the adaptive threshold, so digital silence or a very loud room
+/// can't produce an unusable value
+const MIN_ADAPTIVE_THRESHOLD: f32 = 0.0005;
+const MAX_ADAPTIVE_THRESHOLD: f32 = 0.05;
+
+/// Percentile of frame energies used as the noise estimate
+///
+/// Low enough that speech at the start of a recording, or within a chunk, doesn't
+/// inflate the estimate: the pauses between words still show the background level.
+const NOISE_PERCENTILE: f32 = 0.2;
+
+/// Estimates the ambient noise floor of the microphone
+///
+/// The first `calibration_ms` of a recording establish the initial estimate. After that
+/// the estimate follows quieter audio quickly, and louder audio slowly and only from
+/// chunks that were not speech, so a long utterance can't raise it.
+pub struct NoiseFloorTracker {
+    calibration_samples: usize,
+    calibration_energies: Vec<f32>,
+    noise_floor: Option<f32>,
+}
+
+impl NoiseFloorTracker {
+    pub fn new(calibration_ms: u64) -> Self {
+        Self {
+            calibration_samples: (calibration_ms as usize * SAMPLE_RATE as usize / 1000).max(FRAME_SAMPLES),
+            calibration_energies: Vec::new(),
+            noise_floor: None,
+        }
+    }
+
+    /// Estimated noise floor (RMS), once calibration has finished
+    pub fn noise_floor(&self) -> Option<f32> {
+        self.noise_floor
+    }
+
+    /// Silence threshold derived from the noise floor, once calibration has finished
+    pub fn threshold(&self) -> Option<f32> {
+        self.noise_floor
+            .map(|floor| (floor * ADAPTIVE_MARGIN).clamp(MIN_ADAPTIVE_THRESHOLD, MAX_ADAPTIVE_THRESHOLD))
+    }
+
+    /// Feed audio from the start of a recording until calibration completes
+    pub fn calibrate(&mut self, audio: &[f32]) {
+        if self.noise_floor.is_some() {
+            return;
+        }
+
+        let needed_frames = self.calibration_samples / FRAME_SAMPLES;
+        for frame in audio.chunks_exact(FRAME_SAMPLES) {
+            if self.calibration_energies.len() >= needed_frames {
+                break;
+            }
+            self.calibration_energies.push(rms(frame));
+        }
+
+        if self.calibration_energies.len() >= needed_frames {
+            self.noise_floor = Some(percentile(&mut self.calibration_energies, NOISE_PERCENTILE));
+            self.calibration_energies.clear();
+        }
+    }
+
+    /// Update the estimate after a chunk has been classified
+    pub fn track(&mut self, audio: &[f32], is_speech: bool) {
+        let Some(floor) = self.noise_floor else {
+            return;
+        };
+
+        let mut energies: Vec<f32> = audio.chunks_exact(FRAME_SAMPLES).map(rms).collect();
+        if energies.is_empty() {
+            return;
+        }
+        let observed = percentile(&mut energies, NOISE_PERCENTILE);
+
+        if observed < floor {
+            self.noise_floor = Some(floor + (observed - floor) * 0.5);
+        } else if !is_speech {
+            self.noise_floor = Some(floor + (observed - floor) * 0.1);
+        }
+    }
+
+    /// Start calibrating again (called when a recording starts)
+    pub fn reset(&mut self) {
+        self.calibration_energies.clear();
+        self.noise_floor = None;
+    }
+}
+
+/// Energy-based detector with a threshold derived from the ambient noise floor
+///
+/// Uses `fallback_threshold` until the calibration period has been seen.
+pub struct AdaptiveRmsVad {
+    fallback_threshold: f32,
+    tracker: NoiseFloorTracker,
+}
+
+impl AdaptiveRmsVad {
+    pub fn new(fallback_threshold: f32, calibration_ms: u64) -> Self {
+        Self {
+            fallback_threshold,
+            tracker: NoiseFloorTracker::new(calibration_ms),
+        }
+    }
+}
+
+impl VoiceActivityDetector for AdaptiveRmsVad {
+    fn is_speech(&mut self, audio: &[f32]) -> bool {
+        if audio.is_empty() {
+            return false;
+        }
+
+        self.tracker.calibrate(audio);
+        let threshold = self.tracker.threshold().unwrap_or(self.fallback_threshold);
+        let is_speech = rms(audio) >= threshold;
+        self.tracker.track(audio, is_speech);
+
+        is_speech
+    }
+
+    fn reset(&mut self) {
+        self.tracker.reset();
+    }
+
+    fn threshold(&self) -> Option<f32> {
+        Some(self.tracker.threshold().unwrap_or(self.fallback_threshold))
+    }
+}
+
+/// Value at fraction `p` (0.0-1.0) of the sorted values (reorders `values`)
+fn percentile(values: &mut [f32], p: f32) -> f32 {
+    values.sort_by(|a, b| a.total_cmp(b));
+    let index = ((values.len() - 1) as f32 * p).round() as usize;
+    values[index]
+}
+
+/// Frame length for spectral analysis (20ms at 16kHz)
+const FRAME_SAMPLES: usize = 320;
+
+/// FFT size (frames are zero-padded)
+const FFT_SIZE: usize = 512;
+
+/// Speech band used for the flatness measure
+const SPEECH_BAND_HZ: (f32, f32) = (250.0, 4000.0);
+
+/// Frames must be this much louder than the noise floor to count as speech
+const NOISE_FLOOR_MARGIN: f32 = 2.5;
+
+/// Spectral flatness above this is treated as noise (1.0 = white noise)
+const MAX_SPEECH_FLATNESS: f32 = 0.35;
+
+/// Zero-crossing rate (crossings per sample) above this is treated as hiss or clicks
+const MAX_SPEECH_ZCR: f32 = 0.35;
+
+/// Minimum fraction of speech frames for a chunk to count as speech
+const MIN_SPEECH_FRAME_RATIO: f32 = 0.2;
+
+/// Spectral detector combining energy, zero-crossing rate and spectral flatness
+///
+/// Runs on the CPU in a few microseconds per 20ms frame.
+pub struct SpectralVad {
+    /// Absolute minimum frame RMS for speech
+    min_energy: f32,
+    /// Tracked background noise level (frame RMS)
+    noise_floor: f32,
+    window: Vec<f32>,
+}
+
+impl SpectralVad {
+    pub fn new(min_energy: f32) -> Self {
+        // Hann window over one frame
+        let window = (0..FRAME_SAMPLES)
+            .map(|i| {
+                let phase = 2.0 * std::f32::consts::PI * i as f32 / (FRAME_SAMPLES - 1) as f32;
+                0.5 - 0.5 * phase.cos()
+            })
+            .collect();
+
+        Self {
+            min_energy,
+            noise_floor: min_energy,
+            window,
+        }
+    }
+
+    /// Classify one frame, updating the noise floor from non-speech frames
+    fn is_speech_frame(&mut self, frame: &[f32]) -> bool {
+        let energy = rms(frame);
+
+        let floor = self.noise_floor;
+        let loud_enough = energy >= self.min_energy && energy >= floor * NOISE_FLOOR_MARGIN;
+
+        let is_speech = loud_enough
+            && zero_crossing_rate(frame) <= MAX_SPEECH_ZCR
+            && self.spectral_flatness(frame) <= MAX_SPEECH_FLATNESS;
+
+        // Follow drops in the background level quickly, rises slowly
+        if !is_speech {
+            let rate = if energy < floor { 0.3 } else { 0.05 };
+            self.noise_floor = floor + (energy - floor) * rate;
+        }
+
+        is_speech
+    }
+
+    /// Spectral flatness (geometric mean / arithmetic mean of power) in the speech band
+    ///
+    /// Close to 1.0 for broadband noise, close to 0.0 for harmonic sounds like voiced speech.
+    fn spectral_flatness(&self, frame: &[f32]) -> f32 {
+        let mut re = vec![0.0f32; FFT_SIZE];
+        let mut im = vec![0.0f32; FFT_SIZE];
+        for (i, (&sample, &w)) in frame.iter().zip(&self.window).enumerate() {
+            re[i] = sample * w;
+        }
+        fft(&mut re, &mut im);
+
+        let bin_hz = SAMPLE_RATE / FFT_SIZE as f32;
+        let low = (SPEECH_BAND_HZ.0 / bin_hz) as usize;
+        let high = ((SPEECH_BAND_HZ.1 / bin_hz) as usize).min(FFT_SIZE / 2);
+
+        let mut log_sum = 0.0f32;
+        let mut sum = 0.0f32;
+        for bin in low..high {
+            let power = re[bin] * re[bin] + im[bin] * im[bin] + 1e-12;
+            log_sum += power.ln();
+            sum += power;
+        }
+
+        let bins = (high - low) as f32;
+        let geometric_mean = (log_sum / bins).exp();
+        let arithmetic_mean = sum / bins;
+        geometric_mean / arithmetic_mean
+    }
+}
+
+impl VoiceActivityDetector for SpectralVad {
+    fn is_speech(&mut self, audio: &[f32]) -> bool {
+        let frames = audio.len() / FRAME_SAMPLES;
+        if frames == 0 {
+            return false;
+        }
+
+        let speech_frames = audio
+            .chunks_exact(FRAME_SAMPLES)
+            .filter(|frame| self.is_speech_frame(frame))
+            .count();
+
+        speech_frames as f32 / frames as f32 >= MIN_SPEECH_FRAME_RATIO
+    }
+
+    fn reset(&mut self) {
+        self.noise_floor = self.min_energy;
+    }
+
+    fn threshold(&self) -> Option<f32> {
+        Some(self.min_energy.max(self.noise_floor * NOISE_FLOOR_MARGIN))
+    }
+}
+
+/// Fraction of adjacent sample pairs that change sign
+fn zero_crossing_rate(frame: &[f32]) -> f32 {
+    if frame.len() < 2 {
+        return 0.0;
+    }
+    let crossings = frame
+        .windows(2)
+        .filter(|pair| (pair[0] >= 0.0) != (pair[1] >= 0.0))
+        .count();
+    crossings as f32 / (frame.len() - 1) as f32
+}
+
+/// In-place iterative radix-2 FFT (length must be a power of two)
+fn fft(re: &mut [f32], im: &mut [f32]) {
+    let n = re.len();
+
+    // Bit-reversal permutation
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let angle = -2.0 * std::f32::consts::PI / len as f32;
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let (sin, cos) = (angle * k as f32).sin_cos();
+                let a = start + k;
+                let b = a + len / 2;
+                let t_re = re[b] * cos - im[b] * sin;
+                let t_im = re[b] * sin + im[b] * cos;
+                re[b] = re[a] - t_re;
+                im[b] = im[a] - t_im;
+                re[a] += t_re;
+                im[a] += t_im;
+            }
+        }
+        len <<= 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Voiced-speech-like signal: harmonics of a 140Hz pitch with a syllable-rate envelope
+    fn voiced(duration_ms: usize, amplitude: f32) -> Vec<f32> {
+        (0..duration_ms * 16)
+            .map(|i| {
+                let t = i as f32 / SAMPLE_RATE;
+                let envelope = 0.6 + 0.4 * (2.0 * std::f32::consts::PI * 4.0 * t).sin();
+                let harmonics: f32 = (1..=12)
+                    .map(|h| (2.0 * std::f32::consts::PI * 140.0 * h as f32 * t).sin() / h as f32)
+                    .sum();
+                amplitude * envelope * harmonics / 3.0
+            })
+            .collect()
+    }
+
+    /// Deterministic white noise
+    fn white_noise(duration_ms: usize, amplitude: f32) -> Vec<f32> {
+        let mut seed: u32 = 0x1234_5678;
+        (0..duration_ms * 16)
+            .map(|_| {
+                seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                amplitude * ((seed >> 8) as f32 / (1u32 << 24) as f32 * 2.0 - 1.0)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_rms_vad_threshold() {
+        let mut vad = RmsVad::new(0.01);
+        assert!(vad.is_speech(&vec![0.1; 4800]));
+        assert!(!vad.is_speech(&vec![0.001; 4800]));
+        assert!(!vad.is_speech(&[]));
+    }
+
+    #[test]
+    fn test_quietest_point_finds_pause() {
+        let mut audio = voiced(1000, 0.3);
+        audio[9600..10240].iter_mut().for_each(|s| *s *= 0.01); // 40ms pause at 600ms
+        let cut = quietest_point(&audio, 320);
+        assert!((9600..10240).contains(&cut), "cut at {}", cut);
+        assert_eq!(quietest_point(&[], 320), 0);
+    }
+
+    #[test]
+    fn test_noise_floor_calibration() {
+        let mut tracker = NoiseFloorTracker::new(300);
+        tracker.calibrate(&white_noise(200, 0.01));
+        assert!(tracker.noise_floor().is_none());
+
+        tracker.calibrate(&white_noise(200, 0.01));
+        let floor = tracker.noise_floor().unwrap();
+        // Uniform noise in [-a, a] has RMS a/sqrt(3)
+        assert!((floor - 0.0058).abs() < 0.0015, "floor = {}", floor);
+        assert!((tracker.threshold().unwrap() - floor * ADAPTIVE_MARGIN).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_adaptive_vad_follows_microphone_level() {
+        // Noisy microphone: hiss well above the fixed 0.003 default
+        let mut vad = AdaptiveRmsVad::new(0.003, 300);
+        assert!(RmsVad::new(0.003).is_speech(&white_noise(300, 0.01)));
+        assert!(!vad.is_speech(&white_noise(300, 0.01))); // Calibrated on this chunk
+        assert!(!vad.is_speech(&white_noise(300, 0.01)));
+        assert!(vad.is_speech(&voiced(300, 0.2)));
+
+        // Quiet microphone: speech below the fixed default is still detected
+        let mut vad = AdaptiveRmsVad::new(0.003, 300);
+        vad.is_speech(&white_noise(300, 0.0003));
+        assert!(!vad.is_speech(&white_noise(300, 0.0003)));
+        assert!(vad.is_speech(&voiced(300, 0.008)));
+        assert!(RmsVad::new(0.003).threshold().unwrap() > rms(&voiced(300, 0.008)));
+    }
+
+    #[test]
+    fn test_adaptive_threshold_not_raised_by_speech() {
+        let mut vad = AdaptiveRmsVad::new(0.003, 300);
+        vad.is_speech(&white_noise(300, 0.01));
+        let threshold = vad.threshold().unwrap();
+
+        for _ in 0..20 {
+            assert!(vad.is_speech(&voiced(300, 0.2)));
+        }
+        assert!(vad.threshold().unwrap() <= threshold);
+
+        vad.reset();
+        assert_eq!(vad.threshold(), Some(0.003));
+    }
+
+    #[test]
+    fn test_spectral_vad_detects_voiced_speech_after_noise() {
+        let mut vad = SpectralVad::new(0.003);
+        assert!(!vad.is_speech(&white_noise(300, 0.01)));
+        assert!(vad.is_speech(&voiced(300, 0.2)));
+    }
+
+    #[test]
+    fn test_spectral_vad_rejects_loud_broadband_noise() {
+        // Loud enough to pass any RMS threshold, but spectrally flat
+        let noise = white_noise(300, 0.1);
+        assert!(RmsVad::new(0.003).is_speech(&noise));
+
+        let mut vad = SpectralVad::new(0.003);
+        for _ in 0..5 {
+            assert!(!vad.is_speech(&noise));
+        }
+    }
+
+    #[test]
+    fn test_spectral_vad_rejects_silence() {
+        let mut vad = SpectralVad::new(0.003);
+        assert!(!vad.is_speech(&vec![0.0; 4800]));
+        assert!(!vad.is_speech(&[0.5; 100])); // Shorter than one frame
+    }
+
+    #[test]
+    fn test_fft_single_tone() {
+        let mut re: Vec<f32> = (0..64)
+            .map(|i| (2.0 * std::f32::consts::PI * 4.0 * i as f32 / 64.0).cos())
+            .collect();
+        let mut im = vec![0.0; 64];
+        fft(&mut re, &mut im);
+
+        assert!((re[4] - 32.0).abs() < 1e-3);
+        assert!(re[5].abs() < 1e-3);
+    }
+}
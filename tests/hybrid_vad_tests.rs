@@ -1,6 +1,7 @@
 use std::fs;
 use std::path::PathBuf;
-use live_transcribe::hybrid_vad::simulate_hybrid_vad;
+use live_transcribe::hybrid_vad::{simulate_hybrid_vad, simulate_hybrid_vad_with_detector};
+use live_transcribe::vad::build_detector;
 use live_transcribe::transcription::Transcriber;
 use live_transcribe::config::Config;
 
@@ -90,15 +91,21 @@ fn run_test_case(test: &TestCase) {
 
     // Initialize transcriber
     let config = Config::load_or_create().unwrap_or_default();
-    let transcriber = Transcriber::new(config.transcription).expect("Failed to create transcriber");
+    let transcriber = Transcriber::new(config.transcription.clone()).expect("Failed to create transcriber");
 
     // Run hybrid VAD simulation
-    let result = simulate_hybrid_vad(
-        &samples,
-        &transcriber,
-        300, // 300ms chunks
-        0.02, // silence threshold
-    );
+    let result = if config.transcription.adaptive_threshold {
+        // Threshold calibrated from the recording's noise floor
+        let mut detector = build_detector(&config.transcription);
+        simulate_hybrid_vad_with_detector(&samples, &transcriber, 300, detector.as_mut())
+    } else {
+        simulate_hybrid_vad(
+            &samples,
+            &transcriber,
+            300, // 300ms chunks
+            0.02, // silence threshold
+        )
+    };
 
     println!("\n📊 Results:");
     println!("   Chunks processed: {}", result.chunks_processed);